@@ -0,0 +1,88 @@
+//! Provides non-blocking versions of some of the functions in `filesystem`, for use within a tokio
+//! runtime.
+//!
+//! Each function runs its `filesystem` counterpart on tokio's blocking thread-pool, so that slow
+//! disk access does not stall the executor.
+
+use crate::filesystem::{self, Error};
+use eth2_wallet::{Uuid, Wallet};
+use std::path::PathBuf;
+use tokio::task::spawn_blocking;
+
+/// Read a wallet with the given `uuid` from the `wallet_dir`.
+///
+/// See `filesystem::read`.
+pub async fn read<P: Into<PathBuf>>(wallet_dir: P, uuid: Uuid) -> Result<Wallet, Error> {
+    let wallet_dir = wallet_dir.into();
+    blocking(move || filesystem::read(wallet_dir, &uuid)).await
+}
+
+/// Update the JSON file in the `wallet_dir` with the given `wallet`.
+///
+/// See `filesystem::update`.
+pub async fn update<P: Into<PathBuf>>(wallet_dir: P, wallet: Wallet) -> Result<(), Error> {
+    let wallet_dir = wallet_dir.into();
+    blocking(move || filesystem::update(wallet_dir, &wallet)).await
+}
+
+/// Writes the `wallet` into the `wallet_dir`, returning an error if it already exists. Returns the
+/// path to the wallet JSON file.
+///
+/// See `filesystem::create`.
+pub async fn create<P: Into<PathBuf>>(wallet_dir: P, wallet: Wallet) -> Result<PathBuf, Error> {
+    let wallet_dir = wallet_dir.into();
+    blocking(move || filesystem::create(wallet_dir, &wallet)).await
+}
+
+/// Runs `func` on the blocking thread-pool, returning its result.
+async fn blocking<F, T>(func: F) -> Result<T, Error>
+where
+    F: FnOnce() -> Result<T, Error> + Send + 'static,
+    T: Send + 'static,
+{
+    spawn_blocking(func)
+        .await
+        .map_err(|e| Error::BlockingTaskFailed(format!("{:?}", e)))?
+}
+
+#[cfg(test)]
+// These tests are very slow in debug, only test in release.
+#[cfg(not(debug_assertions))]
+mod tests {
+    use super::*;
+    use eth2_wallet::WalletBuilder;
+    use tempfile::tempdir;
+
+    const SEED: &[u8] = &[42; 32];
+    const WALLET_PASSWORD: &[u8] = &[43; 43];
+
+    fn wallet(name: &str) -> Wallet {
+        WalletBuilder::from_seed_bytes(SEED, WALLET_PASSWORD, name.into())
+            .expect("should create builder")
+            .build()
+            .expect("should build wallet")
+    }
+
+    #[tokio::test]
+    async fn create_read_round_trip() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+        let uuid = *wallet.uuid();
+
+        // Keep an independent copy to compare against, since `Wallet` is not `Clone`.
+        let expected = Wallet::from_json_str(&wallet.to_json_string().unwrap()).unwrap();
+
+        create(dir.path(), wallet)
+            .await
+            .expect("should create wallet");
+
+        let read_wallet = read(dir.path(), uuid).await.expect("should read wallet");
+        assert_eq!(read_wallet, expected);
+
+        update(dir.path(), read_wallet)
+            .await
+            .expect("should update wallet");
+
+        assert_eq!(read(dir.path(), uuid).await.unwrap(), expected);
+    }
+}
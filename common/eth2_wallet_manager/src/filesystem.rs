@@ -1,41 +1,441 @@
 //! Provides some CRUD functions for wallets on the filesystem.
 
+use eth2_hashing::hash;
 use eth2_wallet::Error as WalletError;
 use eth2_wallet::{Uuid, Wallet};
-use std::fs::{copy as copy_file, remove_file, OpenOptions};
-use std::io;
+use std::fs::{
+    copy as copy_file, read as read_file, read_dir, read_to_string, remove_file, rename,
+    DirBuilder, File, OpenOptions,
+};
+use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
 
+#[cfg(unix)]
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+
+/// The extension of wallet JSON files. Wallets written by earlier versions have no extension.
+const WALLET_JSON_EXTENSION: &str = ".json";
+
+/// The permissions applied to wallet files on Unix: read/write for the owner only.
+#[cfg(unix)]
+const WALLET_FILE_MODE: u32 = 0o600;
+
+/// The permissions applied to wallet directories on Unix: accessible by the owner only.
+#[cfg(unix)]
+const WALLET_DIR_MODE: u32 = 0o700;
+
 #[derive(Debug)]
 pub enum Error {
     WalletAlreadyExists(PathBuf),
     WalletDoesNotExist(PathBuf),
     WalletBackupAlreadyExists(PathBuf),
+    WalletLocked(PathBuf),
+    StaleWalletLock {
+        path: PathBuf,
+        pid: u32,
+    },
+    WalletTooLarge {
+        path: PathBuf,
+        max_bytes: u64,
+    },
+    WalletNameNotFound(String),
+    DuplicateWalletName(String),
+    UnableToCreateLockfile(io::Error),
+    UnableToRemoveLockfile(io::Error),
     UnableToCreateBackup(io::Error),
     UnableToRemoveBackup(io::Error),
+    UnableToRestoreBackup(io::Error),
     UnableToRemoveWallet(io::Error),
     UnableToCreateWallet(io::Error),
     UnableToReadWallet(io::Error),
+    UnableToReadWalletDir(io::Error),
+    UnableToCreateWalletDir(io::Error),
+    UnableToSyncWallet(io::Error),
+    UnableToReadChecksum(io::Error),
+    UnableToMigrateWallet(io::Error),
+    ChecksumMismatch(PathBuf),
+    UuidMismatch {
+        expected: Uuid,
+        found: Uuid,
+    },
     JsonWriteError(WalletError),
     JsonReadError(WalletError),
+    UnableToReencryptWallet(WalletError),
+    BlockingTaskFailed(String),
+    PermissionDenied(PathBuf),
+    DiskFull(PathBuf),
+    PrimaryAndBackupFailed {
+        primary: Box<Error>,
+        backup: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Converts an `io::Error` encountered whilst accessing `path` into an `Error`.
+    ///
+    /// Permission and disk-space errors are mapped to `Error::PermissionDenied` and
+    /// `Error::DiskFull`, respectively, since they're actionable by an operator. All other errors
+    /// are passed to `fallback`.
+    fn from_io(path: &Path, e: io::Error, fallback: fn(io::Error) -> Self) -> Self {
+        if e.kind() == io::ErrorKind::PermissionDenied {
+            Error::PermissionDenied(path.to_path_buf())
+        } else if is_disk_full(&e) {
+            Error::DiskFull(path.to_path_buf())
+        } else {
+            fallback(e)
+        }
+    }
+}
+
+/// Describes which files exist for a wallet in a `wallet_dir`, as returned by `wallet_status`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WalletStatus {
+    /// The primary JSON file exists.
+    Present,
+    /// The primary JSON file is missing but a backup exists, so the wallet may be restored with
+    /// `recover`.
+    BackupOnly,
+    /// Neither the primary JSON file nor a backup exists.
+    Absent,
+}
+
+/// The file from which `read_resilient` read a wallet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WalletSource {
+    /// The primary JSON file.
+    Primary,
+    /// The backup JSON file, since the primary was missing or invalid.
+    Backup,
+}
+
+/// Creates the `wallet_dir` (and any missing parents) if it does not already exist.
+///
+/// This should be called before the first `create` into a new `wallet_dir`. On Unix, any newly
+/// created directories are only accessible by their owner.
+pub fn create_wallet_dir<P: AsRef<Path>>(wallet_dir: P) -> Result<(), Error> {
+    let wallet_dir = wallet_dir.as_ref();
+
+    let mut builder = DirBuilder::new();
+    builder.recursive(true);
+
+    #[cfg(unix)]
+    builder.mode(WALLET_DIR_MODE);
+
+    builder
+        .create(wallet_dir)
+        .map_err(|e| Error::from_io(wallet_dir, e, Error::UnableToCreateWalletDir))
 }
 
 /// Read a wallet with the given `uuid` from the `wallet_dir`.
+///
+/// Wallets written by earlier versions (without a `.json` extension) are renamed to the current
+/// naming scheme before being read.
+///
+/// If a `<uuid>.sha256` checksum file exists, the wallet JSON is verified against it and
+/// `Error::ChecksumMismatch` is returned if it has been modified. Wallets without a checksum (e.g.,
+/// those created by earlier versions) are read without verification.
+///
+/// Returns `Error::UuidMismatch` if the file contains a wallet with a UUID other than `uuid`.
 pub fn read<P: AsRef<Path>>(wallet_dir: P, uuid: &Uuid) -> Result<Wallet, Error> {
+    instrument("read", uuid, || {
+        let wallet_dir = wallet_dir.as_ref();
+        migrate_legacy_paths(wallet_dir, uuid)?;
+
+        let json_path = wallet_json_path(wallet_dir, uuid);
+
+        if !json_path.exists() {
+            Err(Error::WalletDoesNotExist(json_path))
+        } else {
+            read_verified(
+                &json_path,
+                &wallet_checksum_path(wallet_dir, uuid),
+                uuid,
+                None,
+            )
+        }
+    })
+}
+
+/// As per `read`, but never modifies the `wallet_dir`.
+///
+/// A wallet written by an earlier version (without a `.json` extension) is read from its legacy
+/// path rather than being renamed. Useful when listing wallets, where the lock for each wallet is
+/// not held.
+pub fn read_in_place<P: AsRef<Path>>(wallet_dir: P, uuid: &Uuid) -> Result<Wallet, Error> {
+    let wallet_dir = wallet_dir.as_ref();
+
+    let json_path = wallet_json_path(wallet_dir, uuid);
+    let legacy_json_path = legacy_wallet_json_path(wallet_dir, uuid);
+
+    let path = if json_path.exists() {
+        json_path
+    } else if legacy_json_path.exists() {
+        legacy_json_path
+    } else {
+        return Err(Error::WalletDoesNotExist(json_path));
+    };
+
+    read_verified(&path, &wallet_checksum_path(wallet_dir, uuid), uuid, None)
+}
+
+/// As per `read`, but returns `Error::WalletTooLarge` instead of reading more than `max_bytes`
+/// from the wallet JSON file.
+///
+/// Guards against exhausting memory when reading an unexpectedly large (or malicious) file.
+pub fn read_with_limit<P: AsRef<Path>>(
+    wallet_dir: P,
+    uuid: &Uuid,
+    max_bytes: u64,
+) -> Result<Wallet, Error> {
+    let wallet_dir = wallet_dir.as_ref();
+    migrate_legacy_paths(wallet_dir, uuid)?;
+
     let json_path = wallet_json_path(wallet_dir, uuid);
 
     if !json_path.exists() {
         Err(Error::WalletDoesNotExist(json_path))
     } else {
-        OpenOptions::new()
-            .read(true)
-            .create(false)
-            .open(json_path)
-            .map_err(Error::UnableToReadWallet)
-            .and_then(|f| Wallet::from_json_reader(f).map_err(Error::JsonReadError))
+        read_verified(
+            &json_path,
+            &wallet_checksum_path(wallet_dir, uuid),
+            uuid,
+            Some(max_bytes),
+        )
+    }
+}
+
+/// Reads the wallet JSON at `json_path`, verifying it against the checksum at `checksum_path` if
+/// that file exists and that it contains a wallet with the given `uuid`.
+///
+/// If `max_bytes` is `Some`, returns `Error::WalletTooLarge` rather than reading more than that
+/// many bytes.
+fn read_verified(
+    json_path: &Path,
+    checksum_path: &Path,
+    uuid: &Uuid,
+    max_bytes: Option<u64>,
+) -> Result<Wallet, Error> {
+    let bytes = if let Some(max_bytes) = max_bytes {
+        let mut bytes = vec![];
+
+        // Read one byte past the limit, so we can tell if the file exceeds it.
+        File::open(json_path)
+            .and_then(|file| {
+                file.take(max_bytes.saturating_add(1))
+                    .read_to_end(&mut bytes)
+            })
+            .map_err(|e| Error::from_io(json_path, e, Error::UnableToReadWallet))?;
+
+        if bytes.len() as u64 > max_bytes {
+            return Err(Error::WalletTooLarge {
+                path: json_path.to_path_buf(),
+                max_bytes,
+            });
+        }
+
+        bytes
+    } else {
+        read_file(json_path).map_err(|e| Error::from_io(json_path, e, Error::UnableToReadWallet))?
+    };
+
+    if checksum_path.exists() {
+        let expected = read_to_string(checksum_path)
+            .map_err(|e| Error::from_io(checksum_path, e, Error::UnableToReadChecksum))?;
+
+        if expected.trim() != checksum(&bytes) {
+            return Err(Error::ChecksumMismatch(json_path.to_path_buf()));
+        }
+    }
+
+    let wallet = Wallet::from_json_reader(bytes.as_slice()).map_err(Error::JsonReadError)?;
+
+    if wallet.uuid() != uuid {
+        return Err(Error::UuidMismatch {
+            expected: *uuid,
+            found: *wallet.uuid(),
+        });
+    }
+
+    Ok(wallet)
+}
+
+/// Returns the UUIDs of all wallets in the `wallet_dir`, sorted in ascending order.
+///
+/// Only files with a name that is a valid UUID are included; backups, temporary files and any
+/// other entries are ignored.
+pub fn list_wallets<P: AsRef<Path>>(wallet_dir: P) -> Result<Vec<Uuid>, Error> {
+    let wallet_dir = wallet_dir.as_ref();
+    let read_dir_error = |e| Error::from_io(wallet_dir, e, Error::UnableToReadWalletDir);
+    let mut uuids = vec![];
+
+    for entry in read_dir(wallet_dir).map_err(read_dir_error)? {
+        let entry = entry.map_err(read_dir_error)?;
+
+        if !entry.file_type().map_err(read_dir_error)?.is_file() {
+            continue;
+        }
+
+        // Wallets written by earlier versions have no extension.
+        if let Some(uuid) = entry.file_name().to_str().and_then(|name| {
+            let name = if name.ends_with(WALLET_JSON_EXTENSION) {
+                &name[..name.len() - WALLET_JSON_EXTENSION.len()]
+            } else {
+                name
+            };
+            Uuid::parse_str(name).ok()
+        }) {
+            uuids.push(uuid)
+        }
+    }
+
+    uuids.sort();
+    uuids.dedup();
+
+    Ok(uuids)
+}
+
+/// Read the wallet with the given `name` from the `wallet_dir`.
+///
+/// Every wallet in the `wallet_dir` is read, so this is slower than `read`.
+///
+/// ## Errors
+///
+/// - `Error::WalletNameNotFound` if no wallet has the given `name`.
+/// - `Error::DuplicateWalletName` if more than one wallet has the given `name`.
+/// - Any error from reading one of the wallets in the `wallet_dir`.
+pub fn read_by_name<P: AsRef<Path>>(wallet_dir: P, name: &str) -> Result<Wallet, Error> {
+    let wallet_dir = wallet_dir.as_ref();
+
+    let mut matches = vec![];
+    for uuid in list_wallets(wallet_dir)? {
+        let wallet = read(wallet_dir, &uuid)?;
+        if wallet.name() == name {
+            matches.push(wallet);
+        }
+    }
+
+    match matches.len() {
+        0 => Err(Error::WalletNameNotFound(name.to_string())),
+        1 => Ok(matches.remove(0)),
+        _ => Err(Error::DuplicateWalletName(name.to_string())),
+    }
+}
+
+/// Returns the status of the wallet with the given `uuid` in the `wallet_dir`, without reading it.
+///
+/// Recovery tooling may use this to determine whether or not `recover` is required.
+pub fn wallet_status<P: AsRef<Path>>(wallet_dir: P, uuid: &Uuid) -> WalletStatus {
+    let wallet_dir = wallet_dir.as_ref();
+
+    if wallet_json_path(wallet_dir, uuid).exists()
+        || legacy_wallet_json_path(wallet_dir, uuid).exists()
+    {
+        WalletStatus::Present
+    } else if wallet_json_backup_path(wallet_dir, uuid).exists()
+        || legacy_wallet_json_backup_path(wallet_dir, uuid).exists()
+    {
+        WalletStatus::BackupOnly
+    } else {
+        WalletStatus::Absent
+    }
+}
+
+/// Read the wallet with the given `uuid` from the `wallet_dir`, falling back to its backup if the
+/// primary JSON file is missing or fails verification (e.g., after an unclean shutdown). Returns
+/// the wallet and the file it was read from.
+///
+/// The backup is verified in the same manner as the primary. Unlike `recover`, the backup is not
+/// moved back into place.
+///
+/// If the primary fails and there is no backup, the error from the primary is returned. If both
+/// fail, returns `Error::PrimaryAndBackupFailed`.
+pub fn read_resilient<P: AsRef<Path>>(
+    wallet_dir: P,
+    uuid: &Uuid,
+) -> Result<(Wallet, WalletSource), Error> {
+    let wallet_dir = wallet_dir.as_ref();
+
+    let primary = match read(wallet_dir, uuid) {
+        Ok(wallet) => return Ok((wallet, WalletSource::Primary)),
+        Err(e) => e,
+    };
+
+    let json_backup_path = wallet_json_backup_path(wallet_dir, uuid);
+    if !json_backup_path.exists() {
+        return Err(primary);
+    }
+
+    // `update` removes the checksum after the primary and only writes a new one after the new
+    // primary, so if the primary is missing any checksum still describes the backup (and if there
+    // is none, the backup is read without verification). If the primary is present the checksum
+    // may describe it instead, in which case the backup fails verification.
+    match read_verified(
+        &json_backup_path,
+        &wallet_checksum_path(wallet_dir, uuid),
+        uuid,
+        None,
+    ) {
+        Ok(wallet) => {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                uuid = %uuid,
+                error = ?primary,
+                "Read wallet from backup"
+            );
+
+            Ok((wallet, WalletSource::Backup))
+        }
+        Err(backup) => Err(Error::PrimaryAndBackupFailed {
+            primary: Box::new(primary),
+            backup: Box::new(backup),
+        }),
     }
 }
 
+/// Read the wallet with the given `uuid` from the `wallet_dir`, restoring it from its backup if
+/// required.
+///
+/// If `update` is interrupted after removing the primary JSON file but before writing the new one,
+/// only the backup file will remain. In that case the backup is read and, if it contains a wallet
+/// with the expected `uuid`, it is moved back to the primary location.
+///
+/// If the primary JSON file exists this function is equivalent to `read`.
+///
+/// The wallet is inspected whilst holding its `WalletLock`, so returns `Error::WalletLocked` if
+/// another process is modifying it.
+pub fn recover<P: AsRef<Path>>(wallet_dir: P, uuid: &Uuid) -> Result<Wallet, Error> {
+    let wallet_dir = wallet_dir.as_ref();
+
+    with_lock(wallet_dir, uuid, || {
+        migrate_legacy_paths(wallet_dir, uuid)?;
+
+        let json_path = wallet_json_path(wallet_dir, uuid);
+        let json_backup_path = wallet_json_backup_path(wallet_dir, uuid);
+
+        if json_path.exists() {
+            return read(wallet_dir, uuid);
+        } else if !json_backup_path.exists() {
+            return Err(Error::WalletDoesNotExist(json_path));
+        }
+
+        // `update` removes the checksum after the primary and only writes a new one after the new
+        // primary, so any checksum still describes the backup. If `update` was interrupted after
+        // removing the checksum, the backup is read without verification.
+        let wallet = read_verified(
+            &json_backup_path,
+            &wallet_checksum_path(wallet_dir, uuid),
+            uuid,
+            None,
+        )?;
+
+        rename(&json_backup_path, &json_path)
+            .map_err(|e| Error::from_io(&json_path, e, Error::UnableToRestoreBackup))?;
+        sync_dir(wallet_dir)?;
+
+        Ok(wallet)
+    })
+}
+
 /// Update the JSON file in the `wallet_dir` with the given `wallet`.
 ///
 /// Performs a three-step copy:
@@ -43,55 +443,1783 @@ pub fn read<P: AsRef<Path>>(wallet_dir: P, uuid: &Uuid) -> Result<Wallet, Error>
 /// 1. Copy the current JSON file to a backup file.
 /// 2. Over-write the existing JSON file.
 /// 3. Delete the backup file.
+///
+/// Returns `Error::WalletLocked` if another process is modifying the wallet.
 pub fn update<P: AsRef<Path>>(wallet_dir: P, wallet: &Wallet) -> Result<(), Error> {
+    instrument("update", wallet.uuid(), || {
+        let wallet_dir = wallet_dir.as_ref();
+        with_lock(wallet_dir, wallet.uuid(), || {
+            migrate_legacy_paths(wallet_dir, wallet.uuid())?;
+
+            update_unlocked(wallet_dir, wallet)
+        })
+    })
+}
+
+/// Checks that `update` would be able to write the `wallet` into the `wallet_dir`, without
+/// modifying the filesystem.
+///
+/// Returns the same errors as `update` for a missing wallet, a stale backup, a held lock or a
+/// wallet which cannot be serialized. Useful for confirming with a user before an `update`.
+///
+/// Note: another process may modify the `wallet_dir` between this check and a subsequent `update`.
+pub fn update_dry_run<P: AsRef<Path>>(wallet_dir: P, wallet: &Wallet) -> Result<(), Error> {
     let wallet_dir = wallet_dir.as_ref();
 
+    let lock_path = wallet_lock_path(wallet_dir, wallet.uuid());
+    if lock_path.exists() {
+        return Err(held_lock_error(lock_path));
+    }
+
+    check_update_preconditions(wallet_dir, wallet)?;
+
+    let mut json = vec![];
+    wallet
+        .to_json_writer(&mut json)
+        .map_err(Error::JsonWriteError)?;
+
+    Ok(())
+}
+
+/// Checks that a wallet with the same UUID as `wallet` exists in the `wallet_dir` and that there is
+/// no existing backup of it.
+///
+/// Files with legacy names are considered, since `update` migrates them before writing.
+fn check_update_preconditions(wallet_dir: &Path, wallet: &Wallet) -> Result<(), Error> {
     let json_path = wallet_json_path(wallet_dir, wallet.uuid());
     let json_backup_path = wallet_json_backup_path(wallet_dir, wallet.uuid());
+    let legacy_json_backup_path = legacy_wallet_json_backup_path(wallet_dir, wallet.uuid());
 
     // Require that a wallet already exists.
-    if !json_path.exists() {
-        return Err(Error::WalletDoesNotExist(json_path));
+    if !json_path.exists() && !legacy_wallet_json_path(wallet_dir, wallet.uuid()).exists() {
+        Err(Error::WalletDoesNotExist(json_path))
     // Require that there is no existing backup.
     } else if json_backup_path.exists() {
-        return Err(Error::WalletBackupAlreadyExists(json_backup_path));
+        Err(Error::WalletBackupAlreadyExists(json_backup_path))
+    } else if legacy_json_backup_path.exists() {
+        Err(Error::WalletBackupAlreadyExists(legacy_json_backup_path))
+    } else {
+        Ok(())
+    }
+}
+
+/// As per `update`, but first retains a copy of the current wallet JSON as `<uuid>.json.bak.1`.
+///
+/// Existing history files are rotated (`<uuid>.json.bak.1` becomes `<uuid>.json.bak.2`, etc.) such
+/// that at most `keep` previous versions are retained. If `keep == 0` no history is retained.
+pub fn update_with_history<P: AsRef<Path>>(
+    wallet_dir: P,
+    wallet: &Wallet,
+    keep: usize,
+) -> Result<(), Error> {
+    let wallet_dir = wallet_dir.as_ref();
+    with_lock(wallet_dir, wallet.uuid(), || {
+        migrate_legacy_paths(wallet_dir, wallet.uuid())?;
+
+        // Check before rotating, so that the history is untouched if the update would fail.
+        check_update_preconditions(wallet_dir, wallet)?;
+
+        let json_path = wallet_json_path(wallet_dir, wallet.uuid());
+
+        // Remove the oldest history file (and any beyond `keep`, e.g. if it was previously
+        // larger), so that each file is shifted back into a free slot. Renaming onto an existing
+        // file fails on some platforms.
+        remove_history(wallet_dir, wallet.uuid(), keep.max(1))?;
+
+        if keep > 0 {
+            // Shift each history file back by one, starting with the oldest.
+            for i in (1..keep).rev() {
+                let from = wallet_history_path(wallet_dir, wallet.uuid(), i);
+                let to = wallet_history_path(wallet_dir, wallet.uuid(), i + 1);
+                if from.exists() {
+                    rename(&from, &to)
+                        .map_err(|e| Error::from_io(&to, e, Error::UnableToCreateBackup))?;
+                }
+            }
+
+            let history_path = wallet_history_path(wallet_dir, wallet.uuid(), 1);
+            copy_file(&json_path, &history_path)
+                .map_err(|e| Error::from_io(&history_path, e, Error::UnableToCreateBackup))?;
+        }
+
+        update_unlocked(wallet_dir, wallet)
+    })
+}
+
+/// Removes the contiguous run of history files starting at `<uuid>.json.bak.<first>`.
+fn remove_history(wallet_dir: &Path, uuid: &Uuid, first: usize) -> Result<(), Error> {
+    let mut i = first;
+    loop {
+        let path = wallet_history_path(wallet_dir, uuid, i);
+        if !path.exists() {
+            return Ok(());
+        }
+        remove_file(&path).map_err(|e| Error::from_io(&path, e, Error::UnableToRemoveBackup))?;
+        i += 1;
     }
+}
+
+/// As per `update`, but assumes the caller already holds the `WalletLock`.
+fn update_unlocked(wallet_dir: &Path, wallet: &Wallet) -> Result<(), Error> {
+    let json_path = wallet_json_path(wallet_dir, wallet.uuid());
+    let json_backup_path = wallet_json_backup_path(wallet_dir, wallet.uuid());
+
+    check_update_preconditions(wallet_dir, wallet)?;
 
     // Copy the existing wallet to the backup location.
-    copy_file(&json_path, &json_backup_path).map_err(Error::UnableToCreateBackup)?;
+    copy_file(&json_path, &json_backup_path)
+        .map_err(|e| Error::from_io(&json_backup_path, e, Error::UnableToCreateBackup))?;
 
     // Remove the existing wallet
-    remove_file(json_path).map_err(Error::UnableToRemoveWallet)?;
+    remove_file(&json_path)
+        .map_err(|e| Error::from_io(&json_path, e, Error::UnableToRemoveWallet))?;
 
     // Create the new wallet.
-    create(wallet_dir, wallet)?;
+    create_unlocked(wallet_dir, wallet)?;
 
     // Remove the backup file.
-    remove_file(json_backup_path).map_err(Error::UnableToRemoveBackup)?;
+    remove_file(&json_backup_path)
+        .map_err(|e| Error::from_io(&json_backup_path, e, Error::UnableToRemoveBackup))?;
 
     Ok(())
 }
 
-/// Writes the `wallet` into the `wallet_dir`, returning an error if it already exists.
-pub fn create<P: AsRef<Path>>(wallet_dir: P, wallet: &Wallet) -> Result<(), Error> {
+/// Removes the wallet with the given `uuid` from the `wallet_dir`, along with any backup and
+/// history files.
+///
+/// Succeeds if either the primary JSON file or the backup exists (e.g., after an interrupted
+/// `update`), otherwise returns `Error::WalletDoesNotExist`.
+pub fn delete_wallet<P: AsRef<Path>>(wallet_dir: P, uuid: &Uuid) -> Result<(), Error> {
+    instrument("delete", uuid, || {
+        let wallet_dir = wallet_dir.as_ref();
+        with_lock(wallet_dir, uuid, || {
+            migrate_legacy_paths(wallet_dir, uuid)?;
+
+            let json_path = wallet_json_path(wallet_dir, uuid);
+            let json_backup_path = wallet_json_backup_path(wallet_dir, uuid);
+
+            let json_exists = json_path.exists();
+            let backup_exists = json_backup_path.exists();
+
+            if !json_exists && !backup_exists {
+                return Err(Error::WalletDoesNotExist(json_path));
+            }
+
+            if json_exists {
+                remove_file(&json_path)
+                    .map_err(|e| Error::from_io(&json_path, e, Error::UnableToRemoveWallet))?;
+            }
+
+            if backup_exists {
+                remove_file(&json_backup_path).map_err(|e| {
+                    Error::from_io(&json_backup_path, e, Error::UnableToRemoveBackup)
+                })?;
+            }
+
+            let checksum_path = wallet_checksum_path(wallet_dir, uuid);
+            if checksum_path.exists() {
+                remove_file(&checksum_path)
+                    .map_err(|e| Error::from_io(&checksum_path, e, Error::UnableToRemoveWallet))?;
+            }
+
+            remove_history(wallet_dir, uuid, 1)
+        })
+    })
+}
+
+/// Copies the wallet with the given `src_uuid` from the `wallet_dir` into the `dst_wallet_dir`,
+/// preserving its UUID. Returns the UUID of the copy.
+///
+/// Returns `Error::WalletAlreadyExists` if the `dst_wallet_dir` already holds a wallet with that
+/// UUID. The copy is written via `create`, so a partial copy is never observed.
+pub fn copy_wallet<P: AsRef<Path>, Q: AsRef<Path>>(
+    wallet_dir: P,
+    src_uuid: &Uuid,
+    dst_wallet_dir: Q,
+) -> Result<Uuid, Error> {
+    let wallet = read(wallet_dir, src_uuid)?;
+
+    create(dst_wallet_dir, &wallet)?;
+
+    Ok(*wallet.uuid())
+}
+
+/// Parses the wallet JSON in `json` and writes it into the `wallet_dir`, returning its UUID.
+///
+/// Returns `Error::JsonReadError` if `json` is not a valid wallet, or `Error::WalletAlreadyExists`
+/// if a wallet with the same UUID is already present. Nothing is written in either case.
+pub fn import_wallet<P: AsRef<Path>>(wallet_dir: P, json: &[u8]) -> Result<Uuid, Error> {
+    let wallet = Wallet::from_json_reader(json).map_err(Error::JsonReadError)?;
+
+    create(wallet_dir, &wallet)?;
+
+    Ok(*wallet.uuid())
+}
+
+/// Re-encrypts the wallet with the given `uuid` in the `wallet_dir`, replacing `old_password` with
+/// `new_password`.
+///
+/// The new wallet is written via `update`, so the existing file is retained as a backup until the
+/// new one is in place. Returns `Error::UnableToReencryptWallet` if `old_password` is unable to
+/// decrypt the wallet (or `new_password` is empty), without modifying the `wallet_dir`.
+pub fn reencrypt_wallet<P: AsRef<Path>>(
+    wallet_dir: P,
+    uuid: &Uuid,
+    old_password: &[u8],
+    new_password: &[u8],
+) -> Result<(), Error> {
+    instrument("reencrypt", uuid, || {
+        let wallet_dir = wallet_dir.as_ref();
+        with_lock(wallet_dir, uuid, || {
+            let wallet = read(wallet_dir, uuid)?
+                .reencrypt(old_password, new_password)
+                .map_err(Error::UnableToReencryptWallet)?;
+
+            update_unlocked(wallet_dir, &wallet)
+        })
+    })
+}
+
+/// Writes the `wallet` into the `wallet_dir`, returning an error if it already exists. Returns the
+/// path to the wallet JSON file.
+///
+/// The wallet is first written (and synced) to a temporary file, which is then atomically renamed
+/// to the final path. This ensures a partially-written wallet is never observed at the final path.
+pub fn create<P: AsRef<Path>>(wallet_dir: P, wallet: &Wallet) -> Result<PathBuf, Error> {
+    instrument("create", wallet.uuid(), || {
+        let wallet_dir = wallet_dir.as_ref();
+        with_lock(wallet_dir, wallet.uuid(), || {
+            create_unlocked(wallet_dir, wallet)
+        })
+    })
+}
+
+/// As per `create`, but also returns `Error::DuplicateWalletName` if a wallet with the same name
+/// already exists in the `wallet_dir`.
+///
+/// Wallets in the `wallet_dir` which cannot be read (e.g., they are corrupt or fail checksum
+/// verification) are skipped when checking for the name, so they do not prevent creation.
+///
+/// Note: only the lock for the new wallet's UUID is held, not a lock over the `wallet_dir`. The name
+/// check is therefore not atomic with respect to other processes, which may concurrently create a
+/// wallet with a different UUID and the same name.
+pub fn create_checked<P: AsRef<Path>>(wallet_dir: P, wallet: &Wallet) -> Result<PathBuf, Error> {
+    let wallet_dir = wallet_dir.as_ref();
+    with_lock(wallet_dir, wallet.uuid(), || {
+        let name_taken = list_wallets(wallet_dir)?.iter().any(|uuid| {
+            read_in_place(wallet_dir, uuid)
+                .map(|existing| existing.name() == wallet.name())
+                .unwrap_or(false)
+        });
+
+        if name_taken {
+            Err(Error::DuplicateWalletName(wallet.name().to_string()))
+        } else {
+            create_unlocked(wallet_dir, wallet)
+        }
+    })
+}
+
+/// Runs `func`, which performs the given wallet `operation` on the wallet with `uuid`.
+///
+/// With the `tracing` feature enabled, `func` runs within a span which records the `operation`,
+/// the `uuid` and its outcome. Only these fields (and the `Debug` of any `Error`) are recorded, so
+/// no secret material is ever logged.
+fn instrument<T>(
+    operation: &'static str,
+    uuid: &Uuid,
+    func: impl FnOnce() -> Result<T, Error>,
+) -> Result<T, Error> {
+    #[cfg(feature = "tracing")]
+    {
+        let span = tracing::info_span!(
+            "wallet",
+            operation,
+            uuid = %uuid,
+            outcome = tracing::field::Empty,
+            error = tracing::field::Empty
+        );
+
+        let result = span.in_scope(func);
+
+        match &result {
+            Ok(_) => {
+                span.record("outcome", &"ok");
+            }
+            Err(e) => {
+                span.record("outcome", &"error");
+                span.record("error", &tracing::field::debug(e));
+            }
+        }
+
+        result
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = (operation, uuid);
+        func()
+    }
+}
+
+/// As per `create`, but assumes the caller already holds the `WalletLock`.
+fn create_unlocked(wallet_dir: &Path, wallet: &Wallet) -> Result<PathBuf, Error> {
     let json_path = wallet_json_path(wallet_dir, wallet.uuid());
+    let checksum_path = wallet_checksum_path(wallet_dir, wallet.uuid());
 
     if json_path.exists() {
-        Err(Error::WalletAlreadyExists(json_path))
+        return Err(Error::WalletAlreadyExists(json_path));
+    }
+
+    let legacy_json_path = legacy_wallet_json_path(wallet_dir, wallet.uuid());
+    if legacy_json_path.exists() {
+        return Err(Error::WalletAlreadyExists(legacy_json_path));
+    }
+
+    let json = wallet.to_json_string().map_err(Error::JsonWriteError)?;
+
+    // Remove any stale checksum before writing the wallet. If we're interrupted, it's better to
+    // leave a wallet without a checksum (which is read without verification) than one with the
+    // wrong checksum.
+    if checksum_path.exists() {
+        remove_file(&checksum_path)
+            .map_err(|e| Error::from_io(&checksum_path, e, Error::UnableToCreateWallet))?;
+    }
+
+    write_atomic(wallet_dir, &json_path, json.as_bytes())?;
+    write_atomic(
+        wallet_dir,
+        &checksum_path,
+        checksum(json.as_bytes()).as_bytes(),
+    )?;
+
+    Ok(json_path)
+}
+
+/// Writes `bytes` to `path` via a temporary file, which is synced to disk and then atomically
+/// renamed into place. This ensures a partially-written file is never observed at `path`.
+///
+/// On Unix, a newly created file is only readable and writable by its owner.
+fn write_atomic(dir: &Path, path: &Path, bytes: &[u8]) -> Result<(), Error> {
+    let temp_path = temp_path(path);
+
+    let write_temp = || {
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+
+        #[cfg(unix)]
+        options.mode(WALLET_FILE_MODE);
+
+        let mut file = options
+            .open(&temp_path)
+            .map_err(|e| Error::from_io(&temp_path, e, Error::UnableToCreateWallet))?;
+        file.write_all(bytes)
+            .map_err(|e| Error::from_io(&temp_path, e, Error::UnableToCreateWallet))?;
+        file.sync_all()
+            .map_err(|e| Error::from_io(&temp_path, e, Error::UnableToSyncWallet))
+    };
+
+    let result = write_temp()
+        .and_then(|()| {
+            rename(&temp_path, path)
+                .map_err(|e| Error::from_io(path, e, Error::UnableToCreateWallet))
+        })
+        // Ensure the rename itself is durable.
+        .and_then(|()| sync_dir(dir));
+
+    if result.is_err() {
+        // Don't leave a partially-written file lying around. If this removal fails, the temporary
+        // file will be truncated and replaced by the next write.
+        let _ = remove_file(&temp_path);
+    }
+
+    result
+}
+
+/// Renames the primary and backup JSON files for the wallet with `uuid` from their legacy names
+/// (without a `.json` extension) to their current names, if they have not been migrated already.
+fn migrate_legacy_paths(wallet_dir: &Path, uuid: &Uuid) -> Result<(), Error> {
+    let paths = [
+        (
+            legacy_wallet_json_path(wallet_dir, uuid),
+            wallet_json_path(wallet_dir, uuid),
+        ),
+        (
+            legacy_wallet_json_backup_path(wallet_dir, uuid),
+            wallet_json_backup_path(wallet_dir, uuid),
+        ),
+    ];
+
+    let mut migrated = false;
+
+    for (legacy_path, path) in &paths {
+        if legacy_path.exists() && !path.exists() {
+            match rename(legacy_path, path) {
+                Ok(()) => migrated = true,
+                // Another process may have migrated the file concurrently.
+                Err(_) if path.exists() => {}
+                Err(e) => return Err(Error::from_io(path, e, Error::UnableToMigrateWallet)),
+            }
+        }
+    }
+
+    if migrated {
+        sync_dir(wallet_dir)?;
+    }
+
+    Ok(())
+}
+
+/// Returns `true` if `e` indicates there is no space left on the device (i.e., `ENOSPC`).
+fn is_disk_full(e: &io::Error) -> bool {
+    /// The value of `ENOSPC` on Linux, macOS and the BSDs.
+    const ENOSPC: i32 = 28;
+
+    cfg!(unix) && e.raw_os_error() == Some(ENOSPC)
+}
+
+/// Returns the hex-encoded SHA256 digest of `bytes`.
+fn checksum(bytes: &[u8]) -> String {
+    hex::encode(hash(bytes))
+}
+
+/// Flushes the directory entries of `dir` to disk, so that any prior renames survive a power loss.
+///
+/// Directories cannot be opened as files on Windows, so this is a no-op on non-Unix platforms.
+fn sync_dir(dir: &Path) -> Result<(), Error> {
+    if cfg!(unix) {
+        File::open(dir)
+            .and_then(|dir| dir.sync_all())
+            .map_err(|e| Error::from_io(dir, e, Error::UnableToSyncWallet))
     } else {
-        OpenOptions::new()
+        Ok(())
+    }
+}
+
+/// An exclusive lock over modifications to a single wallet, held until it is released.
+///
+/// The lock is a `<uuid>.lock` file created atomically alongside the wallet, containing the PID of
+/// the process holding it. It is distinct from the `.lock` file used by `LockedWallet`, which
+/// guards the entire wallet directory.
+///
+/// If a process exits without releasing the lock (e.g., it crashed), the lockfile remains and all
+/// subsequent modifications fail. Where it can be determined that the holding process no longer
+/// exists, `Error::StaleWalletLock` is returned instead of `Error::WalletLocked`. In either case,
+/// once an operator has confirmed that no process is modifying the wallet, the lock can be
+/// recovered by manually deleting the `<uuid>.lock` file.
+struct WalletLock {
+    path: Option<PathBuf>,
+}
+
+impl WalletLock {
+    /// Acquires the lock for the wallet with the given `uuid`, returning `Error::WalletLocked` (or
+    /// `Error::StaleWalletLock`) if it is already held.
+    fn acquire(wallet_dir: &Path, uuid: &Uuid) -> Result<Self, Error> {
+        let path = wallet_lock_path(wallet_dir, uuid);
+
+        let mut file = OpenOptions::new()
             .write(true)
             .create_new(true)
-            .open(json_path)
-            .map_err(Error::UnableToCreateWallet)
-            .and_then(|f| wallet.to_json_writer(f).map_err(Error::JsonWriteError))
+            .open(&path)
+            .map_err(|e| {
+                if e.kind() == io::ErrorKind::AlreadyExists {
+                    held_lock_error(path.clone())
+                } else {
+                    Error::from_io(&path, e, Error::UnableToCreateLockfile)
+                }
+            })?;
+
+        // Hold the lock before writing the PID, so that it's released if the write fails.
+        let lock = Self {
+            path: Some(path.clone()),
+        };
+
+        write!(file, "{}", std::process::id())
+            .and_then(|()| file.sync_all())
+            .map_err(|e| Error::from_io(&path, e, Error::UnableToCreateLockfile))?;
+
+        Ok(lock)
+    }
+
+    /// Releases the lock, returning an error if the lockfile could not be removed.
+    fn release(mut self) -> Result<(), Error> {
+        match self.path.take() {
+            Some(path) => remove_file(&path)
+                .map_err(|e| Error::from_io(&path, e, Error::UnableToRemoveLockfile)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for WalletLock {
+    /// Release the lock when returning early due to an error.
+    ///
+    /// Any error removing the lockfile is ignored in favour of the error being returned. A lockfile
+    /// left behind is reported by the next attempt to acquire the lock.
+    fn drop(&mut self) {
+        if let Some(path) = self.path.take() {
+            let _ = remove_file(path);
+        }
+    }
+}
+
+/// Runs `func` whilst holding the `WalletLock` for the wallet with `uuid` in the `wallet_dir`.
+///
+/// If `func` succeeds, the lock is explicitly released so that any error removing the lockfile is
+/// returned.
+fn with_lock<T>(
+    wallet_dir: &Path,
+    uuid: &Uuid,
+    func: impl FnOnce() -> Result<T, Error>,
+) -> Result<T, Error> {
+    let lock = WalletLock::acquire(wallet_dir, uuid)?;
+    let result = func()?;
+    lock.release()?;
+
+    Ok(result)
+}
+
+/// Returns the error for an attempt to acquire the existing lock at `path`.
+///
+/// Returns `Error::StaleWalletLock` if the lock is known to be held by a process which no longer
+/// exists, otherwise `Error::WalletLocked`.
+fn held_lock_error(path: PathBuf) -> Error {
+    let pid = read_to_string(&path)
+        .ok()
+        .and_then(|contents| contents.trim().parse::<u32>().ok());
+
+    match pid {
+        Some(pid) if !process_exists(pid) => Error::StaleWalletLock { path, pid },
+        _ => Error::WalletLocked(path),
     }
 }
 
+/// Returns `false` if it is known that no process with the given `pid` exists.
+///
+/// This is only known on Linux (via `/proc`). On other platforms it is assumed that the process
+/// exists.
+fn process_exists(pid: u32) -> bool {
+    if cfg!(target_os = "linux") {
+        Path::new("/proc").join(pid.to_string()).exists()
+    } else {
+        true
+    }
+}
+
+fn wallet_lock_path<P: AsRef<Path>>(wallet_dir: P, uuid: &Uuid) -> PathBuf {
+    wallet_dir.as_ref().join(format!("{}.lock", uuid))
+}
+
 fn wallet_json_backup_path<P: AsRef<Path>>(wallet_dir: P, uuid: &Uuid) -> PathBuf {
+    wallet_dir
+        .as_ref()
+        .join(format!("{}{}.backup", uuid, WALLET_JSON_EXTENSION))
+}
+
+fn legacy_wallet_json_backup_path<P: AsRef<Path>>(wallet_dir: P, uuid: &Uuid) -> PathBuf {
     wallet_dir.as_ref().join(format!("{}.backup", uuid))
 }
 
+fn wallet_history_path<P: AsRef<Path>>(wallet_dir: P, uuid: &Uuid, i: usize) -> PathBuf {
+    wallet_dir
+        .as_ref()
+        .join(format!("{}{}.bak.{}", uuid, WALLET_JSON_EXTENSION, i))
+}
+
+fn wallet_checksum_path<P: AsRef<Path>>(wallet_dir: P, uuid: &Uuid) -> PathBuf {
+    wallet_dir.as_ref().join(format!("{}.sha256", uuid))
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    temp_path.into()
+}
+
 fn wallet_json_path<P: AsRef<Path>>(wallet_dir: P, uuid: &Uuid) -> PathBuf {
-    wallet_dir.as_ref().join(format!("{}", uuid))
+    wallet_dir
+        .as_ref()
+        .join(format!("{}{}", uuid, WALLET_JSON_EXTENSION))
+}
+
+fn legacy_wallet_json_path<P: AsRef<Path>>(wallet_dir: P, uuid: &Uuid) -> PathBuf {
+    wallet_dir.as_ref().join(format!("{}", uuid))
+}
+
+#[cfg(test)]
+// These tests are very slow in debug, only test in release.
+#[cfg(not(debug_assertions))]
+mod tests {
+    use super::*;
+    use eth2_wallet::WalletBuilder;
+    use std::fs::{metadata, write};
+    use tempfile::tempdir;
+
+    const SEED: &[u8] = &[42; 32];
+    const WALLET_PASSWORD: &[u8] = &[43; 43];
+
+    fn wallet(name: &str) -> Wallet {
+        WalletBuilder::from_seed_bytes(SEED, WALLET_PASSWORD, name.into())
+            .expect("should create builder")
+            .build()
+            .expect("should build wallet")
+    }
+
+    #[test]
+    fn create_and_read() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        let json_path = create(dir.path(), &wallet).expect("should create wallet");
+
+        assert_eq!(json_path, wallet_json_path(dir.path(), wallet.uuid()));
+        assert_eq!(read(dir.path(), wallet.uuid()).unwrap(), wallet);
+        assert!(
+            !temp_path(&wallet_json_path(dir.path(), wallet.uuid())).exists(),
+            "temp file should be removed"
+        );
+    }
+
+    #[test]
+    fn partial_write_is_not_observed() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        // Simulate a crash part-way through writing the temp file.
+        write(
+            temp_path(&wallet_json_path(dir.path(), wallet.uuid())),
+            b"{\"uui",
+        )
+        .unwrap();
+
+        assert!(!wallet_json_path(dir.path(), wallet.uuid()).exists());
+        match read(dir.path(), wallet.uuid()) {
+            Err(Error::WalletDoesNotExist(_)) => {}
+            _ => panic!("expected wallet to not exist"),
+        }
+
+        // A subsequent create should replace the stale temp file.
+        create(dir.path(), &wallet).expect("should create wallet");
+        assert_eq!(read(dir.path(), wallet.uuid()).unwrap(), wallet);
+        assert!(!temp_path(&wallet_json_path(dir.path(), wallet.uuid())).exists());
+    }
+
+    #[test]
+    fn recover_from_backup() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+
+        // Simulate `update` being interrupted after the original was removed.
+        let json_path = wallet_json_path(dir.path(), wallet.uuid());
+        let json_backup_path = wallet_json_backup_path(dir.path(), wallet.uuid());
+        copy_file(&json_path, &json_backup_path).unwrap();
+        remove_file(&json_path).unwrap();
+
+        match read(dir.path(), wallet.uuid()) {
+            Err(Error::WalletDoesNotExist(_)) => {}
+            _ => panic!("expected wallet to not exist"),
+        }
+
+        assert_eq!(recover(dir.path(), wallet.uuid()).unwrap(), wallet);
+        assert!(json_path.exists(), "primary should be restored");
+        assert!(!json_backup_path.exists(), "backup should be consumed");
+        assert_eq!(read(dir.path(), wallet.uuid()).unwrap(), wallet);
+    }
+
+    #[test]
+    fn recover_locked() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+
+        // Simulate `update` being interrupted after the original was removed.
+        let json_path = wallet_json_path(dir.path(), wallet.uuid());
+        let json_backup_path = wallet_json_backup_path(dir.path(), wallet.uuid());
+        copy_file(&json_path, &json_backup_path).unwrap();
+        remove_file(&json_path).unwrap();
+
+        // Whilst another process holds the lock it may be writing a new primary, so the backup
+        // must not be restored.
+        let lock = WalletLock::acquire(dir.path(), wallet.uuid()).unwrap();
+        match recover(dir.path(), wallet.uuid()) {
+            Err(Error::WalletLocked(_)) => {}
+            _ => panic!("expected wallet to be locked"),
+        }
+        assert!(json_backup_path.exists(), "backup should be untouched");
+        assert!(!json_path.exists(), "primary should not be restored");
+        drop(lock);
+
+        assert_eq!(recover(dir.path(), wallet.uuid()).unwrap(), wallet);
+        assert!(json_path.exists(), "primary should be restored");
+    }
+
+    #[test]
+    fn recover_without_checksum() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+
+        // Simulate `update` being interrupted after the checksum was removed, but before the new
+        // wallet was written.
+        let json_path = wallet_json_path(dir.path(), wallet.uuid());
+        let json_backup_path = wallet_json_backup_path(dir.path(), wallet.uuid());
+        let checksum_path = wallet_checksum_path(dir.path(), wallet.uuid());
+        copy_file(&json_path, &json_backup_path).unwrap();
+        remove_file(&json_path).unwrap();
+        remove_file(&checksum_path).unwrap();
+
+        let (resilient, source) = read_resilient(dir.path(), wallet.uuid()).unwrap();
+        assert_eq!(resilient, wallet);
+        assert_eq!(source, WalletSource::Backup);
+
+        assert_eq!(recover(dir.path(), wallet.uuid()).unwrap(), wallet);
+        assert!(json_path.exists(), "primary should be restored");
+        assert!(!checksum_path.exists(), "checksum should not be written");
+        assert_eq!(read(dir.path(), wallet.uuid()).unwrap(), wallet);
+
+        update(dir.path(), &wallet).expect("should update wallet");
+        assert!(checksum_path.exists(), "checksum should be rewritten");
+        assert_eq!(read(dir.path(), wallet.uuid()).unwrap(), wallet);
+    }
+
+    #[test]
+    fn status() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        assert_eq!(
+            wallet_status(dir.path(), wallet.uuid()),
+            WalletStatus::Absent
+        );
+
+        create(dir.path(), &wallet).expect("should create wallet");
+        assert_eq!(
+            wallet_status(dir.path(), wallet.uuid()),
+            WalletStatus::Present
+        );
+
+        let json_path = wallet_json_path(dir.path(), wallet.uuid());
+        copy_file(
+            &json_path,
+            wallet_json_backup_path(dir.path(), wallet.uuid()),
+        )
+        .unwrap();
+        assert_eq!(
+            wallet_status(dir.path(), wallet.uuid()),
+            WalletStatus::Present
+        );
+
+        remove_file(&json_path).unwrap();
+        assert_eq!(
+            wallet_status(dir.path(), wallet.uuid()),
+            WalletStatus::BackupOnly
+        );
+
+        recover(dir.path(), wallet.uuid()).expect("should recover wallet");
+        assert_eq!(
+            wallet_status(dir.path(), wallet.uuid()),
+            WalletStatus::Present
+        );
+    }
+
+    #[test]
+    fn resilient_primary() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        match read_resilient(dir.path(), wallet.uuid()) {
+            Err(Error::WalletDoesNotExist(_)) => {}
+            _ => panic!("expected wallet to not exist"),
+        }
+
+        create(dir.path(), &wallet).expect("should create wallet");
+
+        assert_eq!(
+            read_resilient(dir.path(), wallet.uuid()).unwrap(),
+            (wallet, WalletSource::Primary)
+        );
+    }
+
+    #[test]
+    fn resilient_backup() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+
+        let json_path = wallet_json_path(dir.path(), wallet.uuid());
+        copy_file(
+            &json_path,
+            wallet_json_backup_path(dir.path(), wallet.uuid()),
+        )
+        .unwrap();
+
+        // Corrupt the primary.
+        let json = read_to_string(&json_path).unwrap();
+        write(&json_path, json.replace("cats", "dogs")).unwrap();
+
+        assert_eq!(
+            read_resilient(dir.path(), wallet.uuid()).unwrap(),
+            (wallet, WalletSource::Backup)
+        );
+        assert_eq!(
+            read_to_string(&json_path).unwrap(),
+            json.replace("cats", "dogs"),
+            "primary should not be modified"
+        );
+    }
+
+    #[test]
+    fn resilient_missing_primary() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+
+        let json_path = wallet_json_path(dir.path(), wallet.uuid());
+        rename(
+            &json_path,
+            wallet_json_backup_path(dir.path(), wallet.uuid()),
+        )
+        .unwrap();
+
+        assert_eq!(
+            read_resilient(dir.path(), wallet.uuid()).unwrap(),
+            (wallet, WalletSource::Backup)
+        );
+        assert!(!json_path.exists(), "primary should not be restored");
+    }
+
+    #[test]
+    fn resilient_both_bad() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+
+        let json_path = wallet_json_path(dir.path(), wallet.uuid());
+        let json = read_to_string(&json_path).unwrap();
+        write(&json_path, json.replace("cats", "dogs")).unwrap();
+        write(
+            wallet_json_backup_path(dir.path(), wallet.uuid()),
+            &json[..json.len() / 2],
+        )
+        .unwrap();
+
+        match read_resilient(dir.path(), wallet.uuid()) {
+            Err(Error::PrimaryAndBackupFailed { primary, backup }) => {
+                match *primary {
+                    Error::ChecksumMismatch(_) => {}
+                    e => panic!("expected checksum mismatch, got {:?}", e),
+                }
+                match *backup {
+                    Error::ChecksumMismatch(_) => {}
+                    e => panic!("expected checksum mismatch, got {:?}", e),
+                }
+            }
+            _ => panic!("expected primary and backup to fail"),
+        }
+    }
+
+    #[test]
+    fn recover_without_backup() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        match recover(dir.path(), wallet.uuid()) {
+            Err(Error::WalletDoesNotExist(_)) => {}
+            _ => panic!("expected wallet to not exist"),
+        }
+
+        create(dir.path(), &wallet).expect("should create wallet");
+        assert_eq!(recover(dir.path(), wallet.uuid()).unwrap(), wallet);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn file_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+
+        let mode = |path: &Path| metadata(path).unwrap().permissions().mode() & 0o777;
+
+        let json_path = wallet_json_path(dir.path(), wallet.uuid());
+        assert_eq!(mode(&json_path), 0o600);
+
+        // The backup is a copy of the primary, so it should inherit its permissions.
+        let json_backup_path = wallet_json_backup_path(dir.path(), wallet.uuid());
+        copy_file(&json_path, &json_backup_path).unwrap();
+        assert_eq!(mode(&json_backup_path), 0o600);
+        remove_file(&json_backup_path).unwrap();
+
+        update(dir.path(), &wallet).expect("should update wallet");
+
+        assert_eq!(mode(&json_path), 0o600);
+    }
+
+    #[test]
+    fn list() {
+        let dir = tempdir().unwrap();
+        let wallet_a = wallet("cats");
+        let wallet_b = wallet("dogs");
+
+        assert!(list_wallets(dir.path()).unwrap().is_empty());
+
+        create(dir.path(), &wallet_a).expect("should create wallet a");
+        create(dir.path(), &wallet_b).expect("should create wallet b");
+
+        // Stray files that should not be listed.
+        copy_file(
+            wallet_json_path(dir.path(), wallet_a.uuid()),
+            wallet_json_backup_path(dir.path(), wallet_a.uuid()),
+        )
+        .unwrap();
+        write(dir.path().join("not-a-uuid"), b"").unwrap();
+        std::fs::create_dir(dir.path().join(format!("{}", Uuid::new_v4()))).unwrap();
+
+        let mut expected = vec![*wallet_a.uuid(), *wallet_b.uuid()];
+        expected.sort();
+
+        assert_eq!(list_wallets(dir.path()).unwrap(), expected);
+    }
+
+    #[test]
+    fn delete() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+        delete_wallet(dir.path(), wallet.uuid()).expect("should delete wallet");
+
+        assert!(!wallet_json_path(dir.path(), wallet.uuid()).exists());
+        assert!(list_wallets(dir.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn delete_backup_only() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+
+        let json_path = wallet_json_path(dir.path(), wallet.uuid());
+        let json_backup_path = wallet_json_backup_path(dir.path(), wallet.uuid());
+        rename(&json_path, &json_backup_path).unwrap();
+
+        delete_wallet(dir.path(), wallet.uuid()).expect("should delete wallet");
+
+        assert!(!json_path.exists());
+        assert!(!json_backup_path.exists());
+    }
+
+    #[test]
+    fn delete_missing() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        match delete_wallet(dir.path(), wallet.uuid()) {
+            Err(Error::WalletDoesNotExist(_)) => {}
+            _ => panic!("expected wallet to not exist"),
+        }
+    }
+
+    #[test]
+    fn lock() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+
+        let lock = WalletLock::acquire(dir.path(), wallet.uuid()).expect("should acquire lock");
+
+        match WalletLock::acquire(dir.path(), wallet.uuid()) {
+            Err(Error::WalletLocked(_)) => {}
+            _ => panic!("expected second lock to fail"),
+        }
+        match update(dir.path(), &wallet) {
+            Err(Error::WalletLocked(_)) => {}
+            _ => panic!("expected update to fail whilst locked"),
+        }
+        match delete_wallet(dir.path(), wallet.uuid()) {
+            Err(Error::WalletLocked(_)) => {}
+            _ => panic!("expected delete to fail whilst locked"),
+        }
+
+        drop(lock);
+
+        assert!(!wallet_lock_path(dir.path(), wallet.uuid()).exists());
+        update(dir.path(), &wallet).expect("should update once unlocked");
+    }
+
+    #[test]
+    fn lock_released_on_error() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+
+        match create(dir.path(), &wallet) {
+            Err(Error::WalletAlreadyExists(_)) => {}
+            _ => panic!("expected wallet already exists error"),
+        }
+
+        assert!(!wallet_lock_path(dir.path(), wallet.uuid()).exists());
+    }
+
+    #[test]
+    fn lock_release() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+        let lock_path = wallet_lock_path(dir.path(), wallet.uuid());
+
+        let lock = WalletLock::acquire(dir.path(), wallet.uuid()).expect("should acquire lock");
+        assert_eq!(
+            read_to_string(&lock_path).unwrap(),
+            std::process::id().to_string(),
+            "lockfile should hold the pid"
+        );
+
+        lock.release().expect("should release lock");
+        assert!(!lock_path.exists());
+
+        let lock = WalletLock::acquire(dir.path(), wallet.uuid()).expect("should reacquire lock");
+        remove_file(&lock_path).unwrap();
+        match lock.release() {
+            Err(Error::UnableToRemoveLockfile(_)) => {}
+            _ => panic!("expected release to fail without a lockfile"),
+        }
+    }
+
+    #[test]
+    fn stale_lock() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+        let lock_path = wallet_lock_path(dir.path(), wallet.uuid());
+
+        create(dir.path(), &wallet).expect("should create wallet");
+
+        // Simulate a lock left behind by a process which crashed. No process can have this pid,
+        // since it exceeds the maximum on Linux.
+        let pid = u32::MAX;
+        write(&lock_path, pid.to_string()).unwrap();
+
+        match update(dir.path(), &wallet) {
+            Err(Error::StaleWalletLock { path, pid: found }) if cfg!(target_os = "linux") => {
+                assert_eq!(path, lock_path);
+                assert_eq!(found, pid);
+            }
+            Err(Error::WalletLocked(_)) if !cfg!(target_os = "linux") => {}
+            _ => panic!("expected update to fail whilst locked"),
+        }
+        match update_dry_run(dir.path(), &wallet) {
+            Err(Error::StaleWalletLock { .. }) if cfg!(target_os = "linux") => {}
+            Err(Error::WalletLocked(_)) if !cfg!(target_os = "linux") => {}
+            _ => panic!("expected dry run to fail whilst locked"),
+        }
+
+        // A lock which doesn't hold a pid is never considered stale.
+        write(&lock_path, "").unwrap();
+        match update(dir.path(), &wallet) {
+            Err(Error::WalletLocked(_)) => {}
+            _ => panic!("expected update to fail whilst locked"),
+        }
+
+        // Manually removing the lockfile recovers the wallet.
+        remove_file(&lock_path).unwrap();
+        update(dir.path(), &wallet).expect("should update once lockfile removed");
+    }
+
+    #[test]
+    fn sync() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        sync_dir(dir.path()).expect("should sync directory");
+
+        match sync_dir(&dir.path().join("missing")) {
+            Err(Error::UnableToSyncWallet(_)) => {}
+            _ => panic!("expected sync of missing directory to fail"),
+        }
+
+        create(dir.path(), &wallet).expect("should create and sync wallet");
+        update(dir.path(), &wallet).expect("should update and sync wallet");
+        assert_eq!(read(dir.path(), wallet.uuid()).unwrap(), wallet);
+    }
+
+    #[test]
+    fn checksum_valid() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+
+        let json = read_file(wallet_json_path(dir.path(), wallet.uuid())).unwrap();
+        let stored = read_to_string(wallet_checksum_path(dir.path(), wallet.uuid())).unwrap();
+        assert_eq!(stored, checksum(&json));
+
+        assert_eq!(read(dir.path(), wallet.uuid()).unwrap(), wallet);
+
+        update(dir.path(), &wallet).expect("should update wallet");
+        assert_eq!(read(dir.path(), wallet.uuid()).unwrap(), wallet);
+    }
+
+    #[test]
+    fn checksum_tampered() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+
+        // Replace the wallet with another valid wallet JSON, so only the checksum can catch it.
+        let json_path = wallet_json_path(dir.path(), wallet.uuid());
+        let json = read_to_string(&json_path).unwrap();
+        write(&json_path, json.replace("cats", "dogs")).unwrap();
+
+        match read(dir.path(), wallet.uuid()) {
+            Err(Error::ChecksumMismatch(path)) => assert_eq!(path, json_path),
+            _ => panic!("expected checksum mismatch"),
+        }
+    }
+
+    #[test]
+    fn uuid_mismatch() {
+        let dir = tempdir().unwrap();
+        let other = wallet("dogs");
+        let wallet = wallet("cats");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+
+        // Simulate a mis-named file.
+        rename(
+            wallet_json_path(dir.path(), wallet.uuid()),
+            wallet_json_path(dir.path(), other.uuid()),
+        )
+        .unwrap();
+
+        match read(dir.path(), other.uuid()) {
+            Err(Error::UuidMismatch { expected, found }) => {
+                assert_eq!(&expected, other.uuid());
+                assert_eq!(&found, wallet.uuid());
+            }
+            _ => panic!("expected uuid mismatch"),
+        }
+    }
+
+    #[test]
+    fn checksum_missing() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+        remove_file(wallet_checksum_path(dir.path(), wallet.uuid())).unwrap();
+
+        assert_eq!(read(dir.path(), wallet.uuid()).unwrap(), wallet);
+    }
+
+    #[test]
+    fn history() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+
+        for _ in 0..3 {
+            update_with_history(dir.path(), &wallet, 2).expect("should update wallet");
+        }
+
+        let history_path = |i| wallet_history_path(dir.path(), wallet.uuid(), i);
+
+        assert_eq!(
+            history_path(1),
+            dir.path().join(format!("{}.json.bak.1", wallet.uuid()))
+        );
+        assert!(history_path(1).exists());
+        assert!(history_path(2).exists());
+        assert!(!history_path(3).exists());
+
+        let bak_count = read_dir(dir.path())
+            .unwrap()
+            .filter(|entry| {
+                entry
+                    .as_ref()
+                    .unwrap()
+                    .file_name()
+                    .to_str()
+                    .unwrap()
+                    .contains(".bak.")
+            })
+            .count();
+        assert_eq!(bak_count, 2);
+        assert_eq!(read(dir.path(), wallet.uuid()).unwrap(), wallet);
+
+        // With a full history, the oldest file is dropped and the others are shifted back.
+        write(history_path(1), b"newest").unwrap();
+        write(history_path(2), b"oldest").unwrap();
+        let current = read_file(wallet_json_path(dir.path(), wallet.uuid())).unwrap();
+        update_with_history(dir.path(), &wallet, 2).expect("should update full history");
+        assert_eq!(read_file(history_path(1)).unwrap(), current);
+        assert_eq!(read_file(history_path(2)).unwrap(), b"newest");
+        assert!(!history_path(3).exists());
+
+        // Reducing `keep` should prune the excess history.
+        update_with_history(dir.path(), &wallet, 1).expect("should update wallet");
+        assert!(history_path(1).exists());
+        assert!(!history_path(2).exists());
+
+        // A failed update should not rotate the history.
+        copy_file(
+            wallet_json_path(dir.path(), wallet.uuid()),
+            wallet_json_backup_path(dir.path(), wallet.uuid()),
+        )
+        .unwrap();
+        let history = read_file(history_path(1)).unwrap();
+        match update_with_history(dir.path(), &wallet, 2) {
+            Err(Error::WalletBackupAlreadyExists(_)) => {}
+            _ => panic!("expected backup to already exist"),
+        }
+        assert_eq!(read_file(history_path(1)).unwrap(), history);
+        assert!(!history_path(2).exists());
+        remove_file(wallet_json_backup_path(dir.path(), wallet.uuid())).unwrap();
+
+        delete_wallet(dir.path(), wallet.uuid()).expect("should delete wallet");
+        assert!(!history_path(1).exists());
+    }
+
+    #[test]
+    fn io_error_classification() {
+        let path = PathBuf::from("/wallets/cats");
+
+        match Error::from_io(
+            &path,
+            io::Error::from(io::ErrorKind::PermissionDenied),
+            Error::UnableToReadWallet,
+        ) {
+            Error::PermissionDenied(p) => assert_eq!(p, path),
+            e => panic!("expected permission denied, got {:?}", e),
+        }
+
+        match Error::from_io(
+            &path,
+            io::Error::from(io::ErrorKind::NotFound),
+            Error::UnableToReadWallet,
+        ) {
+            Error::UnableToReadWallet(e) => assert_eq!(e.kind(), io::ErrorKind::NotFound),
+            e => panic!("expected fallback, got {:?}", e),
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn io_error_disk_full() {
+        let path = PathBuf::from("/wallets/cats");
+
+        match Error::from_io(
+            &path,
+            io::Error::from_raw_os_error(28),
+            Error::UnableToCreateWallet,
+        ) {
+            Error::DiskFull(p) => assert_eq!(p, path),
+            e => panic!("expected disk full, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn create_in_new_dir() {
+        let dir = tempdir().unwrap();
+        let wallet_dir = dir.path().join("a").join("b");
+        let wallet = wallet("cats");
+
+        assert!(create(&wallet_dir, &wallet).is_err());
+
+        create_wallet_dir(&wallet_dir).expect("should create wallet dir");
+        // Should be idempotent.
+        create_wallet_dir(&wallet_dir).expect("should accept existing wallet dir");
+
+        create(&wallet_dir, &wallet).expect("should create wallet");
+        assert_eq!(read(&wallet_dir, wallet.uuid()).unwrap(), wallet);
+
+        #[cfg(unix)]
+        {
+            use std::fs::metadata;
+            use std::os::unix::fs::PermissionsExt;
+
+            let mode = metadata(&wallet_dir).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode, 0o700);
+        }
+    }
+
+    #[test]
+    fn by_name() {
+        let dir = tempdir().unwrap();
+        let cats = wallet("cats");
+        let dogs = wallet("dogs");
+
+        create(dir.path(), &cats).expect("should create cats");
+        create(dir.path(), &dogs).expect("should create dogs");
+
+        assert_eq!(read_by_name(dir.path(), "cats").unwrap(), cats);
+        assert_eq!(read_by_name(dir.path(), "dogs").unwrap(), dogs);
+
+        match read_by_name(dir.path(), "birds") {
+            Err(Error::WalletNameNotFound(name)) => assert_eq!(name, "birds"),
+            _ => panic!("expected name not found"),
+        }
+
+        create(dir.path(), &wallet("cats")).expect("should create second cats");
+
+        match read_by_name(dir.path(), "cats") {
+            Err(Error::DuplicateWalletName(name)) => assert_eq!(name, "cats"),
+            _ => panic!("expected duplicate name"),
+        }
+    }
+
+    #[test]
+    fn create_duplicate_name() {
+        let dir = tempdir().unwrap();
+
+        create_checked(dir.path(), &wallet("cats")).expect("should create first wallet");
+        create_checked(dir.path(), &wallet("dogs")).expect("should create other name");
+
+        match create_checked(dir.path(), &wallet("cats")) {
+            Err(Error::DuplicateWalletName(name)) => assert_eq!(name, "cats"),
+            _ => panic!("expected duplicate name"),
+        }
+        assert_eq!(list_wallets(dir.path()).unwrap().len(), 2);
+
+        // The unchecked path still permits duplicate names.
+        create(dir.path(), &wallet("cats")).expect("should create duplicate name");
+        assert_eq!(list_wallets(dir.path()).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn create_checked_corrupt_neighbour() {
+        let dir = tempdir().unwrap();
+        let corrupt = wallet("cats");
+
+        create(dir.path(), &corrupt).expect("should create wallet");
+        write(
+            wallet_json_path(dir.path(), corrupt.uuid()),
+            b"not a wallet",
+        )
+        .unwrap();
+
+        match read_by_name(dir.path(), "dogs") {
+            Err(Error::ChecksumMismatch(_)) => {}
+            _ => panic!("expected the corrupt wallet to be unreadable"),
+        }
+
+        create_checked(dir.path(), &wallet("dogs")).expect("should skip corrupt wallet");
+        assert_eq!(list_wallets(dir.path()).unwrap().len(), 2);
+
+        match create_checked(dir.path(), &wallet("dogs")) {
+            Err(Error::DuplicateWalletName(name)) => assert_eq!(name, "dogs"),
+            _ => panic!("expected duplicate name"),
+        }
+    }
+
+    #[test]
+    fn read_limit() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+
+        let len = metadata(wallet_json_path(dir.path(), wallet.uuid()))
+            .unwrap()
+            .len();
+
+        assert_eq!(
+            read_with_limit(dir.path(), wallet.uuid(), len).unwrap(),
+            wallet
+        );
+
+        match read_with_limit(dir.path(), wallet.uuid(), 16) {
+            Err(Error::WalletTooLarge { max_bytes, .. }) => assert_eq!(max_bytes, 16),
+            _ => panic!("expected wallet too large"),
+        }
+        match read_with_limit(dir.path(), wallet.uuid(), len - 1) {
+            Err(Error::WalletTooLarge { .. }) => {}
+            _ => panic!("expected wallet too large"),
+        }
+    }
+
+    #[test]
+    fn copy() {
+        let src_dir = tempdir().unwrap();
+        let dst_dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        match copy_wallet(src_dir.path(), wallet.uuid(), dst_dir.path()) {
+            Err(Error::WalletDoesNotExist(_)) => {}
+            _ => panic!("expected wallet to not exist"),
+        }
+
+        create(src_dir.path(), &wallet).expect("should create wallet");
+
+        let uuid =
+            copy_wallet(src_dir.path(), wallet.uuid(), dst_dir.path()).expect("should copy wallet");
+        assert_eq!(&uuid, wallet.uuid());
+        assert_eq!(
+            read(src_dir.path(), &uuid).unwrap(),
+            read(dst_dir.path(), &uuid).unwrap()
+        );
+
+        match copy_wallet(src_dir.path(), wallet.uuid(), dst_dir.path()) {
+            Err(Error::WalletAlreadyExists(_)) => {}
+            _ => panic!("expected wallet to already exist"),
+        }
+        match copy_wallet(src_dir.path(), wallet.uuid(), src_dir.path()) {
+            Err(Error::WalletAlreadyExists(_)) => {}
+            _ => panic!("expected wallet to already exist"),
+        }
+    }
+
+    #[test]
+    fn import() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+        let json = wallet.to_json_string().unwrap();
+
+        let uuid = import_wallet(dir.path(), json.as_bytes()).expect("should import wallet");
+        assert_eq!(&uuid, wallet.uuid());
+        assert_eq!(read(dir.path(), &uuid).unwrap(), wallet);
+
+        match import_wallet(dir.path(), json.as_bytes()) {
+            Err(Error::WalletAlreadyExists(_)) => {}
+            _ => panic!("expected wallet to already exist"),
+        }
+        assert_eq!(read(dir.path(), &uuid).unwrap(), wallet);
+    }
+
+    #[test]
+    fn import_malformed() {
+        let dir = tempdir().unwrap();
+        let json = wallet("cats").to_json_string().unwrap();
+
+        match import_wallet(dir.path(), &json.as_bytes()[..json.len() / 2]) {
+            Err(Error::JsonReadError(_)) => {}
+            _ => panic!("expected json read error"),
+        }
+        assert_eq!(
+            read_dir(dir.path()).unwrap().count(),
+            0,
+            "nothing should be written"
+        );
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn tracing_span() {
+        use std::fmt;
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        /// The fields recorded for a single span.
+        #[derive(Default)]
+        struct Fields(Vec<(String, String)>);
+
+        impl Fields {
+            fn get(&self, name: &str) -> Option<&str> {
+                self.0
+                    .iter()
+                    .rev()
+                    .find(|(field, _)| field == name)
+                    .map(|(_, value)| value.as_str())
+            }
+        }
+
+        impl Visit for Fields {
+            fn record_str(&mut self, field: &Field, value: &str) {
+                self.0.push((field.name().to_string(), value.to_string()));
+            }
+
+            fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+                self.0
+                    .push((field.name().to_string(), format!("{:?}", value)));
+            }
+        }
+
+        /// A subscriber which records the fields of every span.
+        #[derive(Clone, Default)]
+        struct Capture(Arc<Mutex<Vec<Fields>>>);
+
+        impl Subscriber for Capture {
+            fn enabled(&self, _: &Metadata) -> bool {
+                true
+            }
+
+            fn new_span(&self, attributes: &Attributes) -> Id {
+                let mut spans = self.0.lock().unwrap();
+                let mut fields = Fields::default();
+                attributes.record(&mut fields);
+                spans.push(fields);
+                Id::from_u64(spans.len() as u64)
+            }
+
+            fn record(&self, id: &Id, values: &Record) {
+                values.record(&mut self.0.lock().unwrap()[id.into_u64() as usize - 1]);
+            }
+
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+            fn event(&self, _: &Event) {}
+
+            fn enter(&self, _: &Id) {}
+
+            fn exit(&self, _: &Id) {}
+        }
+
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+        let capture = Capture::default();
+
+        tracing::subscriber::with_default(capture.clone(), || {
+            create(dir.path(), &wallet).expect("should create wallet");
+            assert!(create(dir.path(), &wallet).is_err());
+        });
+
+        let spans = capture.0.lock().unwrap();
+        let creates: Vec<&Fields> = spans
+            .iter()
+            .filter(|fields| fields.get("operation") == Some("create"))
+            .collect();
+
+        assert_eq!(creates.len(), 2);
+        for fields in &creates {
+            assert_eq!(fields.get("uuid"), Some(wallet.uuid().to_string().as_str()));
+        }
+        assert_eq!(creates[0].get("outcome"), Some("ok"));
+        assert_eq!(creates[1].get("outcome"), Some("error"));
+        match creates[1].get("error") {
+            Some(error) if error.starts_with("WalletAlreadyExists") => {}
+            other => panic!("unexpected error field: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reencrypt() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+        let new_password: &[u8] = &[44; 44];
+
+        match reencrypt_wallet(dir.path(), wallet.uuid(), WALLET_PASSWORD, new_password) {
+            Err(Error::WalletDoesNotExist(_)) => {}
+            _ => panic!("expected wallet to not exist"),
+        }
+
+        create(dir.path(), &wallet).expect("should create wallet");
+        reencrypt_wallet(dir.path(), wallet.uuid(), WALLET_PASSWORD, new_password)
+            .expect("should reencrypt wallet");
+
+        let reencrypted = read(dir.path(), wallet.uuid()).expect("should read wallet");
+        assert_eq!(reencrypted.uuid(), wallet.uuid());
+        assert_eq!(reencrypted.name(), wallet.name());
+        assert!(reencrypted.decrypt_seed(WALLET_PASSWORD).is_err());
+        assert_eq!(
+            reencrypted.decrypt_seed(new_password).unwrap().as_bytes(),
+            SEED
+        );
+        assert!(!wallet_json_backup_path(dir.path(), wallet.uuid()).exists());
+        assert!(!wallet_lock_path(dir.path(), wallet.uuid()).exists());
+    }
+
+    #[test]
+    fn reencrypt_wrong_password() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+        let json = read_to_string(wallet_json_path(dir.path(), wallet.uuid())).unwrap();
+
+        match reencrypt_wallet(dir.path(), wallet.uuid(), &[1, 2, 3], &[44; 44]) {
+            Err(Error::UnableToReencryptWallet(_)) => {}
+            _ => panic!("expected wallet to not decrypt"),
+        }
+
+        assert_eq!(
+            read_to_string(wallet_json_path(dir.path(), wallet.uuid())).unwrap(),
+            json,
+            "wallet should be unchanged"
+        );
+        assert_eq!(read(dir.path(), wallet.uuid()).unwrap(), wallet);
+        assert!(!wallet_lock_path(dir.path(), wallet.uuid()).exists());
+    }
+
+    #[test]
+    fn update_dry_run_checks() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        let list_dir = || {
+            let mut paths = read_dir(dir.path())
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .collect::<Vec<_>>();
+            paths.sort();
+            paths
+        };
+
+        match update_dry_run(dir.path(), &wallet) {
+            Err(Error::WalletDoesNotExist(_)) => {}
+            _ => panic!("expected wallet to not exist"),
+        }
+        assert!(list_dir().is_empty(), "dir should be untouched");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+        let before = list_dir();
+        let json = read_to_string(wallet_json_path(dir.path(), wallet.uuid())).unwrap();
+
+        update_dry_run(dir.path(), &wallet).expect("should pass dry run");
+        assert_eq!(list_dir(), before, "dir should be untouched");
+        assert_eq!(
+            read_to_string(wallet_json_path(dir.path(), wallet.uuid())).unwrap(),
+            json
+        );
+
+        let lock = WalletLock::acquire(dir.path(), wallet.uuid()).unwrap();
+        match update_dry_run(dir.path(), &wallet) {
+            Err(Error::WalletLocked(_)) => {}
+            _ => panic!("expected wallet to be locked"),
+        }
+        drop(lock);
+
+        copy_file(
+            wallet_json_path(dir.path(), wallet.uuid()),
+            wallet_json_backup_path(dir.path(), wallet.uuid()),
+        )
+        .unwrap();
+        match update_dry_run(dir.path(), &wallet) {
+            Err(Error::WalletBackupAlreadyExists(_)) => {}
+            _ => panic!("expected backup to already exist"),
+        }
+    }
+
+    #[test]
+    fn json_extension() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        let json_path = create(dir.path(), &wallet).expect("should create wallet");
+        assert_eq!(
+            json_path,
+            dir.path().join(format!("{}.json", wallet.uuid()))
+        );
+        assert!(json_path.exists());
+        assert!(!dir.path().join(format!("{}", wallet.uuid())).exists());
+        assert_eq!(
+            wallet_json_backup_path(dir.path(), wallet.uuid()),
+            dir.path().join(format!("{}.json.backup", wallet.uuid()))
+        );
+
+        assert_eq!(list_wallets(dir.path()).unwrap(), vec![*wallet.uuid()]);
+    }
+
+    #[test]
+    fn legacy_migration() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+
+        // Simulate a wallet written by an earlier version.
+        let json_path = wallet_json_path(dir.path(), wallet.uuid());
+        let legacy_json_path = dir.path().join(format!("{}", wallet.uuid()));
+        rename(&json_path, &legacy_json_path).unwrap();
+
+        assert_eq!(list_wallets(dir.path()).unwrap(), vec![*wallet.uuid()]);
+        assert_eq!(
+            wallet_status(dir.path(), wallet.uuid()),
+            WalletStatus::Present
+        );
+        match create(dir.path(), &wallet) {
+            Err(Error::WalletAlreadyExists(path)) => assert_eq!(path, legacy_json_path),
+            _ => panic!("expected wallet to already exist"),
+        }
+        update_dry_run(dir.path(), &wallet).expect("should pass dry run");
+        assert!(legacy_json_path.exists(), "dry run should not migrate");
+
+        assert_eq!(read(dir.path(), wallet.uuid()).unwrap(), wallet);
+        assert!(json_path.exists(), "wallet should be migrated");
+        assert!(!legacy_json_path.exists(), "legacy file should be removed");
+
+        assert_eq!(read(dir.path(), wallet.uuid()).unwrap(), wallet);
+        update(dir.path(), &wallet).expect("should update wallet");
+        assert_eq!(read(dir.path(), wallet.uuid()).unwrap(), wallet);
+    }
+
+    #[test]
+    fn legacy_read_in_place() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+        assert_eq!(read_in_place(dir.path(), wallet.uuid()).unwrap(), wallet);
+
+        // Simulate a wallet written by an earlier version.
+        let json_path = wallet_json_path(dir.path(), wallet.uuid());
+        let legacy_json_path = dir.path().join(format!("{}", wallet.uuid()));
+        rename(&json_path, &legacy_json_path).unwrap();
+
+        assert_eq!(read_in_place(dir.path(), wallet.uuid()).unwrap(), wallet);
+        assert!(
+            legacy_json_path.exists(),
+            "legacy file should not be migrated"
+        );
+        assert!(!json_path.exists(), "wallet should not be migrated");
+
+        remove_file(&legacy_json_path).unwrap();
+        match read_in_place(dir.path(), wallet.uuid()) {
+            Err(Error::WalletDoesNotExist(path)) => assert_eq!(path, json_path),
+            _ => panic!("expected wallet to not exist"),
+        }
+    }
+
+    #[test]
+    fn legacy_backup_migration() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+
+        // Simulate an earlier version's `update` being interrupted after removing the original.
+        let json_path = wallet_json_path(dir.path(), wallet.uuid());
+        let legacy_backup_path = dir.path().join(format!("{}.backup", wallet.uuid()));
+        rename(&json_path, &legacy_backup_path).unwrap();
+
+        assert_eq!(
+            wallet_status(dir.path(), wallet.uuid()),
+            WalletStatus::BackupOnly
+        );
+
+        assert_eq!(recover(dir.path(), wallet.uuid()).unwrap(), wallet);
+        assert!(json_path.exists(), "primary should be restored");
+        assert!(
+            !legacy_backup_path.exists(),
+            "legacy backup should be consumed"
+        );
+        assert!(!wallet_json_backup_path(dir.path(), wallet.uuid()).exists());
+    }
+
+    #[test]
+    fn create_existing() {
+        let dir = tempdir().unwrap();
+        let wallet = wallet("cats");
+
+        create(dir.path(), &wallet).expect("should create wallet");
+
+        match create(dir.path(), &wallet) {
+            Err(Error::WalletAlreadyExists(_)) => {}
+            _ => panic!("expected wallet already exists error"),
+        }
+    }
 }
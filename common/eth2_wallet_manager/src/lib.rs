@@ -1,4 +1,5 @@
-mod filesystem;
+pub mod async_filesystem;
+pub mod filesystem;
 mod locked_wallet;
 mod wallet_manager;
 
@@ -1,11 +1,11 @@
 use crate::{
-    filesystem::{create, Error as FilesystemError},
+    filesystem::{create, read_in_place, Error as FilesystemError},
     LockedWallet,
 };
-use eth2_wallet::{bip39::Mnemonic, Error as WalletError, Uuid, Wallet, WalletBuilder};
+use eth2_wallet::{bip39::Mnemonic, Error as WalletError, Uuid, WalletBuilder};
 use std::collections::HashMap;
 use std::ffi::OsString;
-use std::fs::{create_dir_all, read_dir, OpenOptions};
+use std::fs::{create_dir_all, read_dir};
 use std::io;
 use std::path::{Path, PathBuf};
 
@@ -56,20 +56,30 @@ pub enum WalletType {
 /// Manages a directory containing EIP-2386 wallets.
 ///
 /// Each wallet is stored in a directory with the name of the wallet UUID. Inside each directory a
-/// EIP-2386 JSON wallet is also stored using the UUID as the filename.
+/// EIP-2386 JSON wallet is stored as `<uuid>.json`, alongside a `<uuid>.sha256` checksum of it.
+/// Wallets written by earlier versions have no extension, and are renamed when they are next
+/// read or modified.
 ///
-/// In each wallet directory an optional `.lock` exists to prevent concurrent reads and writes from
-/// the same wallet.
+/// In each wallet directory:
+///
+/// - An optional `.lock` exists whilst a `LockedWallet` is open, to prevent concurrent reads and
+///   writes from the same wallet.
+/// - An optional `<uuid>.lock` exists whilst the wallet JSON is being modified.
+/// - Optional `<uuid>.json.bak.<n>` files hold previous versions of the wallet JSON, where it was
+///   updated with history.
 ///
 /// Example:
 ///
 /// ```ignore
 /// wallets
 /// ├── 35c07717-c6f3-45e8-976f-ef5d267e86c9
-/// │   └── 35c07717-c6f3-45e8-976f-ef5d267e86c9
+/// │   └── 35c07717-c6f3-45e8-976f-ef5d267e86c9.json
+/// │   └── 35c07717-c6f3-45e8-976f-ef5d267e86c9.sha256
 /// └── 747ad9dc-e1a1-4804-ada4-0dc124e46c49
 ///     └── .lock
-///     └── 747ad9dc-e1a1-4804-ada4-0dc124e46c49
+///     └── 747ad9dc-e1a1-4804-ada4-0dc124e46c49.json
+///     └── 747ad9dc-e1a1-4804-ada4-0dc124e46c49.json.bak.1
+///     └── 747ad9dc-e1a1-4804-ada4-0dc124e46c49.sha256
 /// ```
 pub struct WalletManager {
     dir: PathBuf,
@@ -165,17 +175,12 @@ impl WalletManager {
 
                 // Ignore any paths that don't parse as a UUID.
                 if let Ok(uuid) = Uuid::parse_str(&file_name) {
-                    let wallet_path = f.path().join(format!("{}", uuid));
-                    let wallet = OpenOptions::new()
-                        .read(true)
-                        .create(false)
-                        .open(wallet_path)
-                        .map_err(Error::UnableToReadWallet)
-                        .and_then(|f| Wallet::from_json_reader(f).map_err(Error::WalletError))?;
-
-                    if *wallet.uuid() != uuid {
-                        return Err(Error::UuidMismatch((uuid, *wallet.uuid())));
-                    }
+                    let wallet = read_in_place(f.path(), &uuid).map_err(|e| match e {
+                        FilesystemError::UuidMismatch { expected, found } => {
+                            Error::UuidMismatch((expected, found))
+                        }
+                        e => Error::FilesystemError(e),
+                    })?;
 
                     wallets.insert(wallet.name().into(), *wallet.uuid());
                 }
@@ -193,6 +198,7 @@ mod tests {
     use super::*;
     use crate::{filesystem::read, locked_wallet::LOCK_FILE};
     use eth2_wallet::bip39::{Language, Mnemonic};
+    use eth2_wallet::Wallet;
     use tempfile::tempdir;
 
     const MNEMONIC: &str =
@@ -245,7 +251,7 @@ mod tests {
 
     fn json_path<P: AsRef<Path>>(base_dir: P, uuid: &Uuid) -> PathBuf {
         let s = format!("{}", uuid);
-        base_dir.as_ref().join(&s).join(&s)
+        base_dir.as_ref().join(&s).join(format!("{}.json", s))
     }
 
     #[test]
@@ -321,6 +327,26 @@ mod tests {
         assert_eq!(wallets, vec![(name, uuid)]);
     }
 
+    #[test]
+    fn wallets_does_not_migrate() {
+        let dir = tempdir().unwrap();
+        let base_dir = dir.path();
+        let mgr = WalletManager::open(base_dir).unwrap();
+
+        let uuid = *create_wallet(&mgr, 0).wallet().uuid();
+
+        // Simulate a wallet written by an earlier version.
+        let legacy_json_path = wallet_dir_path(base_dir, &uuid).join(format!("{}", uuid));
+        std::fs::rename(json_path(base_dir, &uuid), &legacy_json_path).unwrap();
+
+        let wallets = mgr.wallets().unwrap().into_iter().collect::<Vec<_>>();
+        assert_eq!(wallets, vec![("0".to_string(), uuid)]);
+        assert!(
+            legacy_json_path.exists(),
+            "listing should not migrate the wallet"
+        );
+    }
+
     #[test]
     fn locked_wallet_lockfile() {
         let dir = tempdir().unwrap();
@@ -1084,6 +1084,14 @@ mod bitlist {
                 .highest_set_bit(),
             Some(15)
         );
+
+        // Sparse: the highest of several set bits, separated by an empty byte.
+        assert_eq!(
+            BitList1024::from_raw_bytes(vec![0b0100_0101, 0b0000_0000, 0b0001_0000], 24)
+                .unwrap()
+                .highest_set_bit(),
+            Some(20)
+        );
     }
 
     #[test]
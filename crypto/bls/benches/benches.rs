@@ -0,0 +1,25 @@
+use bls::{PublicKey, SecretKey};
+use criterion::{black_box, criterion_group, criterion_main, Benchmark, Criterion};
+
+fn aggregate_public_keys(c: &mut Criterion) {
+    for &n in &[128, 2_048] {
+        let pubkeys: Vec<PublicKey> = (0..n)
+            .map(|_| PublicKey::from_secret_key(&SecretKey::random()))
+            .collect();
+        let pubkeys_fast = pubkeys.clone();
+
+        c.bench(
+            &format!("aggregate_public_keys/{}_keys", n),
+            Benchmark::new("sequential", move |b| {
+                b.iter(|| black_box(PublicKey::aggregate(&pubkeys).unwrap()))
+            })
+            .with_function("fast", move |b| {
+                b.iter(|| black_box(PublicKey::aggregate_fast(&pubkeys_fast).unwrap()))
+            })
+            .sample_size(10),
+        );
+    }
+}
+
+criterion_group!(benches, aggregate_public_keys);
+criterion_main!(benches);
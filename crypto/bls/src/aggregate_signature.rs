@@ -4,6 +4,7 @@ use serde::de::{Deserialize, Deserializer};
 use serde::ser::{Serialize, Serializer};
 use serde_hex::{encode as hex_encode, PrefixedHexVisitor};
 use ssz::{Decode, DecodeError, Encode};
+use std::ops::AddAssign;
 
 /// A BLS aggregate signature.
 ///
@@ -51,7 +52,7 @@ impl AggregateSignature {
     /// Only returns `true` if the set of keys in the `AggregatePublicKey` match the set of keys
     /// that signed the `AggregateSignature`.
     pub fn verify(&self, msg: &[u8], aggregate_public_key: &AggregatePublicKey) -> bool {
-        if self.is_empty {
+        if self.is_empty || self.is_infinity() {
             return false;
         }
         self.aggregate_signature
@@ -63,7 +64,7 @@ impl AggregateSignature {
     /// Only returns `true` if the set of keys in the `AggregatePublicKey` match the set of keys
     /// that signed the `AggregateSignature`.
     pub fn verify_unaggregated(&self, msg: &[u8], public_keys: &[&PublicKey]) -> bool {
-        if self.is_empty {
+        if self.is_empty || self.is_infinity() {
             return false;
         }
         let public_key_refs: Vec<_> = public_keys.iter().map(|pk| pk.as_raw()).collect();
@@ -71,11 +72,23 @@ impl AggregateSignature {
             .fast_aggregate_verify(msg, &public_key_refs)
     }
 
+    /// Verify the `AggregateSignature` against a single `msg` which was signed by each of the
+    /// `public_keys`.
+    ///
+    /// Equivalent to `FastAggregateVerify` in the BLS signature specification. Always returns
+    /// `false` if `public_keys` is empty.
+    pub fn fast_aggregate_verify(&self, msg: &[u8], public_keys: &[&PublicKey]) -> bool {
+        if public_keys.is_empty() {
+            return false;
+        }
+        self.verify_unaggregated(msg, public_keys)
+    }
+
     /// Verify this AggregateSignature against multiple AggregatePublickeys and Messages.
     ///
     /// Each AggregatePublicKey has a 1:1 ratio with a 32 byte Message.
     pub fn verify_multiple(&self, messages: &[&[u8]], public_keys: &[&PublicKey]) -> bool {
-        if self.is_empty {
+        if self.is_empty || self.is_infinity() {
             return false;
         }
         let public_keys_refs: Vec<_> = public_keys.iter().map(|pk| pk.as_raw()).collect();
@@ -83,6 +96,40 @@ impl AggregateSignature {
             .aggregate_verify(&messages, &public_keys_refs)
     }
 
+    /// Verify this `AggregateSignature` as per `verify_multiple`, but upon failure attempt to
+    /// isolate the offending message/public key pairs.
+    ///
+    /// `signatures` must contain the individual signatures which were aggregated into `self`, in
+    /// the same order as `messages` and `public_keys`. Failing subsets are bisected until each
+    /// invalid pair is found, so this is considerably slower than `verify_multiple` when the
+    /// aggregate is invalid.
+    pub fn verify_detailed(
+        &self,
+        messages: &[&[u8]],
+        public_keys: &[&PublicKey],
+        signatures: &[&Signature],
+    ) -> VerifyReport {
+        if messages.is_empty()
+            || messages.len() != public_keys.len()
+            || messages.len() != signatures.len()
+        {
+            return VerifyReport::LengthMismatch;
+        }
+
+        if self.verify_multiple(messages, public_keys) {
+            return VerifyReport::Valid;
+        }
+
+        let mut invalid = vec![];
+        find_invalid_pairs(messages, public_keys, signatures, 0, &mut invalid);
+
+        if invalid.is_empty() {
+            VerifyReport::AggregateMismatch
+        } else {
+            VerifyReport::InvalidPairs(invalid)
+        }
+    }
+
     /// Return AggregateSignature as bytes
     pub fn as_bytes(&self) -> Vec<u8> {
         if self.is_empty {
@@ -91,13 +138,32 @@ impl AggregateSignature {
         self.aggregate_signature.as_bytes()
     }
 
-    /// Convert bytes to AggregateSignature
+    /// Convert bytes to AggregateSignature.
+    ///
+    /// Returns an error if the bytes are not a valid G2 point or if the point is not in the G2
+    /// subgroup. Signatures outside the subgroup can never be valid, but would otherwise waste a
+    /// pairing during verification.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let signature = Self::from_bytes_unchecked(bytes)?;
+
+        if !signature.is_empty && !signature.aggregate_signature.subgroup_check() {
+            return Err(DecodeError::BytesInvalid(format!(
+                "Invalid AggregateSignature bytes: point is not in the G2 subgroup: {:?}",
+                bytes
+            )));
+        }
+
+        Ok(signature)
+    }
+
+    /// Convert bytes to AggregateSignature without checking that the point is in the G2
+    /// subgroup.
+    pub fn from_bytes_unchecked(bytes: &[u8]) -> Result<Self, DecodeError> {
         for byte in bytes {
             if *byte != 0 {
                 let sig = RawAggregateSignature::from_bytes(&bytes).map_err(|_| {
                     DecodeError::BytesInvalid(format!(
-                        "Invalid AggregateSignature bytes: {:?}",
+                        "Invalid AggregateSignature bytes: not a valid G2 point: {:?}",
                         bytes
                     ))
                 })?;
@@ -129,6 +195,14 @@ impl AggregateSignature {
         self.is_empty
     }
 
+    /// Returns `true` if the underlying signature is the point at infinity (e.g., a new
+    /// `AggregateSignature` to which no signatures have been added).
+    ///
+    /// An infinity signature never passes verification.
+    pub fn is_infinity(&self) -> bool {
+        self.aggregate_signature.point.is_infinity()
+    }
+
     /// Creates a new AggregateSignature
     ///
     /// aggregate_signature set to the point infinity
@@ -147,6 +221,60 @@ impl AggregateSignature {
     }
 }
 
+/// Recursively bisects the given pairs, pushing the index (offset by `offset`) of each pair whose
+/// signature is invalid to `invalid`.
+fn find_invalid_pairs(
+    messages: &[&[u8]],
+    public_keys: &[&PublicKey],
+    signatures: &[&Signature],
+    offset: usize,
+    invalid: &mut Vec<usize>,
+) {
+    let mut aggregate = AggregateSignature::new();
+    signatures
+        .iter()
+        .for_each(|signature| aggregate.add(signature));
+
+    if aggregate.verify_multiple(messages, public_keys) {
+        return;
+    }
+
+    if messages.len() == 1 {
+        invalid.push(offset);
+        return;
+    }
+
+    let mid = messages.len() / 2;
+    find_invalid_pairs(
+        &messages[..mid],
+        &public_keys[..mid],
+        &signatures[..mid],
+        offset,
+        invalid,
+    );
+    find_invalid_pairs(
+        &messages[mid..],
+        &public_keys[mid..],
+        &signatures[mid..],
+        offset + mid,
+        invalid,
+    );
+}
+
+impl AddAssign<&Signature> for AggregateSignature {
+    /// Equivalent to `AggregateSignature::add`.
+    fn add_assign(&mut self, signature: &Signature) {
+        self.add(signature)
+    }
+}
+
+impl AddAssign<&AggregateSignature> for AggregateSignature {
+    /// Equivalent to `AggregateSignature::add_aggregate`.
+    fn add_assign(&mut self, agg_signature: &AggregateSignature) {
+        self.add_aggregate(agg_signature)
+    }
+}
+
 impl_ssz!(
     AggregateSignature,
     BLS_AGG_SIG_BYTE_SIZE,
@@ -190,7 +318,7 @@ impl arbitrary::Arbitrary for AggregateSignature {
 
 #[cfg(test)]
 mod tests {
-    use super::super::{Keypair, Signature};
+    use super::super::{AggregatePublicKey, Keypair, Signature};
     use super::*;
     use ssz::Encode;
 
@@ -206,4 +334,154 @@ mod tests {
 
         assert_eq!(original, decoded);
     }
+
+    /// A point that is on the curve, but not in the G2 subgroup.
+    ///
+    /// Taken from the `deserialization_fails_not_in_G2` BLS test vector.
+    const NOT_IN_G2: &str = "8123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    #[test]
+    pub fn test_from_bytes_not_in_subgroup() {
+        let bytes = hex::decode(NOT_IN_G2).unwrap();
+
+        assert!(AggregateSignature::from_bytes_unchecked(&bytes).is_ok());
+
+        match AggregateSignature::from_ssz_bytes(&bytes) {
+            Err(DecodeError::BytesInvalid(msg)) => assert!(msg.contains("subgroup")),
+            _ => panic!("expected subgroup check to fail"),
+        }
+    }
+
+    #[test]
+    pub fn test_from_bytes_malformed() {
+        let mut bytes = [0; BLS_AGG_SIG_BYTE_SIZE];
+        bytes[0] = 255;
+
+        match AggregateSignature::from_ssz_bytes(&bytes) {
+            Err(DecodeError::BytesInvalid(msg)) => assert!(msg.contains("not a valid G2 point")),
+            _ => panic!("expected malformed bytes to fail"),
+        }
+    }
+
+    #[test]
+    pub fn test_add_assign() {
+        let msg = &[42; 32];
+        let keypairs = [Keypair::random(), Keypair::random()];
+        let signatures: Vec<Signature> = keypairs
+            .iter()
+            .map(|keypair| Signature::new(msg, &keypair.sk))
+            .collect();
+
+        let mut incremental = AggregateSignature::new();
+        for signature in &signatures {
+            incremental += signature;
+        }
+
+        let mut one_shot = AggregateSignature::new();
+        one_shot.add(&signatures[0]);
+        one_shot.add(&signatures[1]);
+
+        assert_eq!(incremental, one_shot);
+
+        let pubkeys: Vec<&PublicKey> = keypairs.iter().map(|keypair| &keypair.pk).collect();
+        assert!(incremental.fast_aggregate_verify(msg, &pubkeys));
+
+        // Combining two single-signature aggregates should give the same result.
+        let mut first = AggregateSignature::new();
+        first += &signatures[0];
+        let mut second = AggregateSignature::new();
+        second += &signatures[1];
+        first += &second;
+
+        assert_eq!(first, one_shot);
+    }
+
+    #[test]
+    pub fn test_is_infinity() {
+        let keypair = Keypair::random();
+        let msg = &[42; 32];
+
+        let mut signature = AggregateSignature::new();
+        assert!(signature.is_infinity());
+        assert!(!signature.is_empty());
+        assert!(!signature.fast_aggregate_verify(msg, &[&keypair.pk]));
+
+        signature.add(&Signature::new(msg, &keypair.sk));
+        assert!(!signature.is_infinity());
+        assert!(signature.fast_aggregate_verify(msg, &[&keypair.pk]));
+    }
+
+    #[test]
+    pub fn test_fast_aggregate_verify() {
+        let msg = &[42; 32];
+        let keypairs = [Keypair::random(), Keypair::random()];
+
+        let mut signature = AggregateSignature::new();
+        keypairs
+            .iter()
+            .for_each(|keypair| signature.add(&Signature::new(msg, &keypair.sk)));
+
+        let pubkeys: Vec<&PublicKey> = keypairs.iter().map(|keypair| &keypair.pk).collect();
+        assert!(signature.fast_aggregate_verify(msg, &pubkeys));
+
+        let other = Keypair::random();
+        let swapped = [pubkeys[0], &other.pk];
+        assert!(!signature.fast_aggregate_verify(msg, &swapped));
+
+        assert!(!signature.fast_aggregate_verify(msg, &[]));
+    }
+
+    #[test]
+    pub fn test_verify_wrong_message() {
+        let msg_a = &[42; 32];
+        let msg_b = &[43; 32];
+        let keypairs = [Keypair::random(), Keypair::random()];
+
+        let mut signature = AggregateSignature::new();
+        let mut aggregate_public_key = AggregatePublicKey::new();
+        for keypair in &keypairs {
+            signature.add(&Signature::new(msg_a, &keypair.sk));
+            aggregate_public_key.add(&keypair.pk);
+        }
+
+        assert!(signature.verify(msg_a, &aggregate_public_key));
+        assert!(!signature.verify(msg_b, &aggregate_public_key));
+    }
+
+    #[test]
+    pub fn test_verify_detailed() {
+        let keypairs: Vec<Keypair> = (0..5).map(|_| Keypair::random()).collect();
+        let messages: Vec<Vec<u8>> = (0..5).map(|i| vec![i as u8; 32]).collect();
+        let signatures: Vec<Signature> = keypairs
+            .iter()
+            .zip(messages.iter())
+            .map(|(keypair, msg)| Signature::new(msg, &keypair.sk))
+            .collect();
+
+        let mut aggregate = AggregateSignature::new();
+        signatures
+            .iter()
+            .for_each(|signature| aggregate.add(signature));
+
+        let pubkeys: Vec<&PublicKey> = keypairs.iter().map(|keypair| &keypair.pk).collect();
+        let signature_refs: Vec<&Signature> = signatures.iter().collect();
+        let mut message_refs: Vec<&[u8]> = messages.iter().map(|msg| msg.as_slice()).collect();
+
+        assert_eq!(
+            aggregate.verify_detailed(&message_refs, &pubkeys, &signature_refs),
+            VerifyReport::Valid
+        );
+
+        let tampered = vec![42; 32];
+        message_refs[3] = &tampered;
+        assert_eq!(
+            aggregate.verify_detailed(&message_refs, &pubkeys, &signature_refs),
+            VerifyReport::InvalidPairs(vec![3])
+        );
+
+        assert_eq!(
+            aggregate.verify_detailed(&message_refs[..4], &pubkeys, &signature_refs),
+            VerifyReport::LengthMismatch
+        );
+    }
 }
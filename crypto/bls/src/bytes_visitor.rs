@@ -0,0 +1,30 @@
+use serde::de::{self, Visitor};
+use std::fmt;
+
+/// Deserializes raw bytes into a `Vec<u8>`.
+///
+/// Used for non-human-readable formats (e.g., bincode), where keys are serialized as bytes rather
+/// than hex. See `serde_hex::PrefixedHexVisitor` for the human-readable counterpart.
+pub struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a byte array")
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(value.to_vec())
+    }
+
+    fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(value)
+    }
+}
@@ -0,0 +1,195 @@
+use super::{PublicKey, BLS_PUBLIC_KEY_BYTE_SIZE};
+use ssz::{Decode, DecodeError, Encode};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use tree_hash::{Hash256, TreeHash, TreeHashType};
+
+/// A `PublicKey` which caches its compressed serialization and tree hash root.
+///
+/// Compressing and hashing a public key is relatively expensive, so both are computed once upon
+/// instantiation. This struct only provides immutable access to the underlying `PublicKey`,
+/// therefore the cached values can never become stale.
+///
+/// Attempting to mutate the underlying key does not compile:
+///
+/// ```compile_fail
+/// use bls::{CachedPublicKey, Keypair};
+///
+/// let mut cached = CachedPublicKey::new(Keypair::random().pk);
+/// *cached = Keypair::random().pk;
+/// ```
+#[derive(Clone)]
+pub struct CachedPublicKey {
+    pubkey: PublicKey,
+    bytes: [u8; BLS_PUBLIC_KEY_BYTE_SIZE],
+    tree_hash_root: Hash256,
+}
+
+impl CachedPublicKey {
+    /// Instantiates `Self`, computing the compressed bytes and tree hash root of `pubkey`.
+    pub fn new(pubkey: PublicKey) -> Self {
+        let mut bytes = [0; BLS_PUBLIC_KEY_BYTE_SIZE];
+        bytes.copy_from_slice(&pubkey.as_bytes());
+        let tree_hash_root = pubkey.tree_hash_root();
+
+        Self {
+            pubkey,
+            bytes,
+            tree_hash_root,
+        }
+    }
+
+    /// Returns the cached compressed bytes of the public key.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns a reference to the underlying public key.
+    pub fn pubkey(&self) -> &PublicKey {
+        &self.pubkey
+    }
+
+    /// Consumes `self`, returning the underlying public key.
+    pub fn into_pubkey(self) -> PublicKey {
+        self.pubkey
+    }
+}
+
+impl From<PublicKey> for CachedPublicKey {
+    fn from(pubkey: PublicKey) -> Self {
+        Self::new(pubkey)
+    }
+}
+
+impl Deref for CachedPublicKey {
+    type Target = PublicKey;
+
+    fn deref(&self) -> &PublicKey {
+        &self.pubkey
+    }
+}
+
+impl PartialEq for CachedPublicKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes[..] == other.bytes[..]
+    }
+}
+
+impl Eq for CachedPublicKey {}
+
+impl Hash for CachedPublicKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bytes.hash(state)
+    }
+}
+
+impl fmt::Debug for CachedPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", serde_hex::encode(&self.bytes[..]))
+    }
+}
+
+impl Encode for CachedPublicKey {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        BLS_PUBLIC_KEY_BYTE_SIZE
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        BLS_PUBLIC_KEY_BYTE_SIZE
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.bytes)
+    }
+}
+
+impl Decode for CachedPublicKey {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        BLS_PUBLIC_KEY_BYTE_SIZE
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let len = bytes.len();
+        let expected = <Self as Decode>::ssz_fixed_len();
+
+        if len != expected {
+            Err(DecodeError::InvalidByteLength { len, expected })
+        } else {
+            let pubkey = PublicKey::from_bytes(bytes)?;
+            let mut cached = [0; BLS_PUBLIC_KEY_BYTE_SIZE];
+            cached.copy_from_slice(bytes);
+            let tree_hash_root = pubkey.tree_hash_root();
+
+            Ok(Self {
+                pubkey,
+                bytes: cached,
+                tree_hash_root,
+            })
+        }
+    }
+}
+
+impl TreeHash for CachedPublicKey {
+    fn tree_hash_type() -> TreeHashType {
+        PublicKey::tree_hash_type()
+    }
+
+    fn tree_hash_packed_encoding(&self) -> Vec<u8> {
+        unreachable!("Vector should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("Vector should never be packed.")
+    }
+
+    fn tree_hash_root(&self) -> Hash256 {
+        self.tree_hash_root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::SecretKey;
+    use super::*;
+
+    #[test]
+    pub fn test_cached_bytes() {
+        let pubkey = PublicKey::from_secret_key(&SecretKey::random());
+        let cached = CachedPublicKey::new(pubkey.clone());
+
+        assert_eq!(cached.as_slice(), &pubkey.as_bytes()[..]);
+        assert_eq!(cached.as_ssz_bytes(), pubkey.as_ssz_bytes());
+        assert_eq!(*cached, pubkey);
+    }
+
+    #[test]
+    pub fn test_cached_tree_hash_root() {
+        let pubkey = PublicKey::from_secret_key(&SecretKey::random());
+        let cached = CachedPublicKey::new(pubkey.clone());
+
+        assert_eq!(cached.tree_hash_root(), pubkey.tree_hash_root());
+
+        let decoded = CachedPublicKey::from_ssz_bytes(&pubkey.as_ssz_bytes()).unwrap();
+        assert_eq!(decoded.tree_hash_root(), pubkey.tree_hash_root());
+    }
+
+    #[test]
+    pub fn test_ssz_round_trip() {
+        let original = CachedPublicKey::new(PublicKey::from_secret_key(&SecretKey::random()));
+
+        let bytes = original.as_ssz_bytes();
+        let decoded = CachedPublicKey::from_ssz_bytes(&bytes).unwrap();
+
+        assert_eq!(original, decoded);
+        assert_eq!(original.pubkey(), decoded.pubkey());
+    }
+}
@@ -1,12 +1,13 @@
 use super::{
     fake_aggregate_public_key::FakeAggregatePublicKey, fake_public_key::FakePublicKey,
-    fake_signature::FakeSignature, BLS_AGG_SIG_BYTE_SIZE,
+    fake_signature::FakeSignature, VerifyReport, BLS_AGG_SIG_BYTE_SIZE,
 };
 use milagro_bls::G2Point;
 use serde::de::{Deserialize, Deserializer};
 use serde::ser::{Serialize, Serializer};
 use serde_hex::{encode as hex_encode, PrefixedHexVisitor};
 use ssz::{ssz_encode, Decode, DecodeError, Encode};
+use std::ops::AddAssign;
 
 /// A BLS aggregate signature.
 ///
@@ -37,6 +38,11 @@ impl FakeAggregateSignature {
         &self
     }
 
+    /// _Always_ returns `false`.
+    pub fn is_infinity(&self) -> bool {
+        false
+    }
+
     /// Does glorious nothing.
     pub fn add(&mut self, _signature: &FakeSignature) {
         // Do nothing.
@@ -57,6 +63,11 @@ impl FakeAggregateSignature {
         true
     }
 
+    /// _Always_ returns `true`.
+    pub fn fast_aggregate_verify(&self, _msg: &[u8], _public_keys: &[&FakePublicKey]) -> bool {
+        true
+    }
+
     /// _Always_ returns `true`.
     pub fn verify_multiple(
         &self,
@@ -66,6 +77,16 @@ impl FakeAggregateSignature {
         true
     }
 
+    /// _Always_ returns `VerifyReport::Valid`.
+    pub fn verify_detailed(
+        &self,
+        _messages: &[&[u8]],
+        _public_keys: &[&FakePublicKey],
+        _signatures: &[&FakeSignature],
+    ) -> VerifyReport {
+        VerifyReport::Valid
+    }
+
     /// _Always_ returns `true`.
     pub fn fast_aggregate_verify_pre_aggregated(
         &self,
@@ -98,11 +119,30 @@ impl FakeAggregateSignature {
         }
     }
 
+    /// Convert bytes to fake BLS aggregate signature
+    pub fn from_bytes_unchecked(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Self::from_bytes(bytes)
+    }
+
     pub fn as_bytes(&self) -> Vec<u8> {
         self.bytes.clone()
     }
 }
 
+impl AddAssign<&FakeSignature> for FakeAggregateSignature {
+    /// Does glorious nothing.
+    fn add_assign(&mut self, _signature: &FakeSignature) {
+        // Do nothing.
+    }
+}
+
+impl AddAssign<&FakeAggregateSignature> for FakeAggregateSignature {
+    /// Does glorious nothing.
+    fn add_assign(&mut self, _agg_sig: &FakeAggregateSignature) {
+        // Do nothing.
+    }
+}
+
 impl_ssz!(
     FakeAggregateSignature,
     BLS_AGG_SIG_BYTE_SIZE,
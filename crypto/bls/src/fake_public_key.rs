@@ -1,10 +1,12 @@
-use super::{SecretKey, BLS_PUBLIC_KEY_BYTE_SIZE};
+use super::{bytes_visitor::BytesVisitor, Error, SecretKey, Signature, BLS_PUBLIC_KEY_BYTE_SIZE};
 use milagro_bls::G1Point;
 use milagro_bls::PublicKey as RawPublicKey;
 use serde::de::{Deserialize, Deserializer};
 use serde::ser::{Serialize, Serializer};
 use serde_hex::{encode as hex_encode, PrefixedHexVisitor};
 use ssz::{ssz_encode, Decode, DecodeError, Encode};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
 use std::default;
 use std::fmt;
 use std::hash::{Hash, Hasher};
@@ -32,6 +34,29 @@ impl FakePublicKey {
         }
     }
 
+    /// Returns `Error::EmptyAggregate` if `keys` is empty, otherwise an all-zero's public key.
+    pub fn aggregate<'a>(keys: impl IntoIterator<Item = &'a FakePublicKey>) -> Result<Self, Error> {
+        if keys.into_iter().next().is_none() {
+            Err(Error::EmptyAggregate)
+        } else {
+            Ok(Self::zero())
+        }
+    }
+
+    /// Returns `Error::EmptyAggregate` if `keys` is empty, otherwise an all-zero's public key.
+    pub fn aggregate_fast(keys: &[FakePublicKey]) -> Result<Self, Error> {
+        Self::aggregate(keys)
+    }
+
+    /// Returns `Error::EmptyAggregate` if `keys` is empty, otherwise an all-zero's public key.
+    pub fn combine_serialized(keys: &[[u8; BLS_PUBLIC_KEY_BYTE_SIZE]]) -> Result<Self, Error> {
+        if keys.is_empty() {
+            Err(Error::EmptyAggregate)
+        } else {
+            Ok(Self::zero())
+        }
+    }
+
     /// Creates a new all-zero's public key
     pub fn zero() -> Self {
         Self {
@@ -53,6 +78,32 @@ impl FakePublicKey {
         })
     }
 
+    /// Converts many compressed public keys into `FakePublicKey`s.
+    pub fn from_bytes_many(
+        bytes: &[[u8; BLS_PUBLIC_KEY_BYTE_SIZE]],
+    ) -> Result<Vec<Self>, (usize, DecodeError)> {
+        bytes
+            .iter()
+            .enumerate()
+            .map(|(i, bytes)| Self::from_bytes(bytes).map_err(|e| (i, e)))
+            .collect()
+    }
+
+    /// Converts compressed bytes to FakePublicKey
+    pub fn from_bytes_unchecked(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Self::from_bytes(bytes)
+    }
+
+    /// Decodes SSZ bytes to FakePublicKey
+    pub fn decode_nonzero(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Self::from_ssz_bytes(bytes)
+    }
+
+    /// _Always_ returns `false`.
+    pub fn is_infinity(&self) -> bool {
+        false
+    }
+
     /// Returns the FakePublicKey as (x, y) bytes
     pub fn as_uncompressed_bytes(&self) -> Vec<u8> {
         self.as_bytes()
@@ -79,6 +130,11 @@ impl FakePublicKey {
         hex_encode(self.as_ssz_bytes())
     }
 
+    /// _Always_ returns `true`.
+    pub fn verify_proof_of_possession(&self, _signature: &Signature) -> bool {
+        true
+    }
+
     // Returns itself
     pub fn as_raw(&self) -> &Self {
         self
@@ -94,8 +150,9 @@ impl FakePublicKey {
 }
 
 impl fmt::Display for FakePublicKey {
+    /// Displays the full `0x`-prefixed hex encoding of the public key, as used by serde.
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.concatenated_hex_id())
+        write!(f, "{}", self.as_hex_string())
     }
 }
 
@@ -117,11 +174,17 @@ impl_ssz!(FakePublicKey, BLS_PUBLIC_KEY_BYTE_SIZE, "FakePublicKey");
 impl_tree_hash!(FakePublicKey, BLS_PUBLIC_KEY_BYTE_SIZE);
 
 impl Serialize for FakePublicKey {
+    /// Serializes as `0x`-prefixed hex for human-readable formats (e.g., JSON) and as raw bytes
+    /// otherwise (e.g., bincode).
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        serializer.serialize_str(&hex_encode(self.as_bytes()))
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&hex_encode(self.as_bytes()))
+        } else {
+            serializer.serialize_bytes(&self.as_bytes())
+        }
     }
 }
 
@@ -130,19 +193,69 @@ impl<'de> Deserialize<'de> for FakePublicKey {
     where
         D: Deserializer<'de>,
     {
-        let bytes = deserializer.deserialize_str(PrefixedHexVisitor)?;
+        let bytes = if deserializer.is_human_readable() {
+            deserializer.deserialize_str(PrefixedHexVisitor)?
+        } else {
+            deserializer.deserialize_bytes(BytesVisitor)?
+        };
+        if bytes.len() != BLS_PUBLIC_KEY_BYTE_SIZE {
+            return Err(serde::de::Error::custom(format!(
+                "invalid pubkey length: expected {} bytes, got {}",
+                BLS_PUBLIC_KEY_BYTE_SIZE,
+                bytes.len()
+            )));
+        }
         let pubkey = Self::from_ssz_bytes(&bytes[..])
             .map_err(|e| serde::de::Error::custom(format!("invalid pubkey ({:?})", e)))?;
         Ok(pubkey)
     }
 }
 
+impl TryFrom<[u8; BLS_PUBLIC_KEY_BYTE_SIZE]> for FakePublicKey {
+    type Error = DecodeError;
+
+    /// Equivalent to `Self::from_bytes`.
+    fn try_from(bytes: [u8; BLS_PUBLIC_KEY_BYTE_SIZE]) -> Result<Self, DecodeError> {
+        Self::from_bytes(&bytes)
+    }
+}
+
+impl TryFrom<&[u8]> for FakePublicKey {
+    type Error = DecodeError;
+
+    /// Equivalent to `Self::from_bytes`, but first returns `DecodeError::InvalidByteLength` if
+    /// `bytes` is not `BLS_PUBLIC_KEY_BYTE_SIZE` long.
+    fn try_from(bytes: &[u8]) -> Result<Self, DecodeError> {
+        if bytes.len() != BLS_PUBLIC_KEY_BYTE_SIZE {
+            return Err(DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: BLS_PUBLIC_KEY_BYTE_SIZE,
+            });
+        }
+
+        Self::from_bytes(bytes)
+    }
+}
+
 impl PartialEq for FakePublicKey {
     fn eq(&self, other: &FakePublicKey) -> bool {
         ssz_encode(self) == ssz_encode(other)
     }
 }
 
+impl PartialOrd for FakePublicKey {
+    fn partial_cmp(&self, other: &FakePublicKey) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FakePublicKey {
+    /// Orders public keys lexicographically by their compressed bytes.
+    fn cmp(&self, other: &FakePublicKey) -> Ordering {
+        self.as_bytes().cmp(&other.as_bytes())
+    }
+}
+
 impl Hash for FakePublicKey {
     /// Note: this is distinct from consensus serialization, it will produce a different hash.
     ///
@@ -74,6 +74,11 @@ impl FakeSignature {
         }
     }
 
+    /// As per `Self::from_bytes`, since there is no subgroup check to skip.
+    pub fn from_bytes_unchecked(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Self::from_bytes(bytes)
+    }
+
     pub fn as_bytes(&self) -> Vec<u8> {
         self.bytes.clone()
     }
@@ -11,11 +11,22 @@ pub struct Keypair {
 impl Keypair {
     /// Instantiate a Keypair using SecretKey::random().
     pub fn random() -> Self {
-        let sk = SecretKey::random();
+        Self::from_secret(SecretKey::random())
+    }
+
+    /// Instantiate a Keypair from a `SecretKey`, deriving the `PublicKey`.
+    pub fn from_secret(sk: SecretKey) -> Self {
         let pk = PublicKey::from_secret_key(&sk);
         Keypair { sk, pk }
     }
 
+    /// Instantiate a Keypair from an existing `SecretKey` and `PublicKey`.
+    ///
+    /// Note: it is not verified that `pk` corresponds to `sk`.
+    pub fn from_components(sk: SecretKey, pk: PublicKey) -> Self {
+        Keypair { sk, pk }
+    }
+
     pub fn identifier(&self) -> String {
         self.pk.concatenated_hex_id()
     }
@@ -39,3 +50,31 @@ impl fmt::Display for Keypair {
         write!(f, "{}", self.pk)
     }
 }
+
+#[cfg(test)]
+#[cfg(not(feature = "fake_crypto"))]
+mod tests {
+    use super::*;
+
+    /// The first interop keypair (see `eth2_interop_keypairs`).
+    const SECRET_KEY: &str = "25295f0d1d592a90b333e26e85149708208e9f8e8bc18f6c77bd62f8ad7a6866";
+    const PUBLIC_KEY: &str = "a99a76ed7796f7be22d5b7e85deeb7c5677e88e511e0b337618f8c4eb61349b4bf2d153f649f7b53359fe8b94a38e44c";
+
+    #[test]
+    pub fn test_from_secret() {
+        let sk = SecretKey::from_bytes(&hex::decode(SECRET_KEY).unwrap()).unwrap();
+        let keypair = Keypair::from_secret(sk);
+
+        assert_eq!(hex::encode(keypair.pk.as_bytes()), PUBLIC_KEY);
+    }
+
+    #[test]
+    pub fn test_from_components() {
+        let sk = SecretKey::random();
+        let pk = PublicKey::from_secret_key(&sk);
+
+        let keypair = Keypair::from_components(sk.clone(), pk.clone());
+        assert!(keypair.sk == sk);
+        assert_eq!(keypair.pk, pk);
+    }
+}
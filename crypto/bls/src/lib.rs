@@ -3,20 +3,25 @@ extern crate ssz;
 
 #[macro_use]
 mod macros;
+mod bytes_visitor;
+mod cached_public_key;
 mod keypair;
 mod plain_text;
 mod public_key_bytes;
 mod secret_key;
 mod signature_bytes;
 mod signature_set;
+mod verify_report;
 
+pub use crate::cached_public_key::CachedPublicKey;
 pub use crate::keypair::Keypair;
 pub use crate::public_key_bytes::PublicKeyBytes;
 pub use crate::secret_key::SecretKey;
 pub use crate::signature_bytes::SignatureBytes;
 pub use milagro_bls::{compress_g2, hash_to_curve_g2};
 pub use plain_text::PlainText;
-pub use signature_set::{verify_signature_sets, SignatureSet};
+pub use signature_set::{batch_verify, verify_signature_sets, SignatureSet};
+pub use verify_report::VerifyReport;
 
 #[cfg(feature = "arbitrary")]
 pub use arbitrary;
@@ -59,13 +64,27 @@ mod reals {
     pub use crate::signature::Signature;
 }
 
+/// Errors that may arise when combining BLS objects.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Error {
+    /// An aggregate was requested over zero public keys.
+    EmptyAggregate,
+    /// The public key at `index` could not be decoded.
+    InvalidPublicKey { index: usize, error: DecodeError },
+    /// The signature of the item at `index` of a batch is invalid.
+    InvalidSignature { index: usize },
+    /// A batch failed to verify, although each of its items verified individually.
+    BatchVerificationFailed,
+}
+
 pub const BLS_AGG_SIG_BYTE_SIZE: usize = 96;
 pub const BLS_SIG_BYTE_SIZE: usize = 96;
 pub const BLS_SECRET_KEY_BYTE_SIZE: usize = 32;
 pub const BLS_PUBLIC_KEY_BYTE_SIZE: usize = 48;
+pub const BLS_PUBLIC_KEY_UNCOMPRESSED_BYTE_SIZE: usize = 96;
 
 use eth2_hashing::hash;
-use ssz::ssz_encode;
+use ssz::{ssz_encode, DecodeError};
 
 /// Returns the withdrawal credentials for a given public key.
 pub fn get_withdrawal_credentials(pubkey: &PublicKey, prefix_byte: u8) -> Vec<u8> {
@@ -76,6 +95,29 @@ pub fn get_withdrawal_credentials(pubkey: &PublicKey, prefix_byte: u8) -> Vec<u8
     prefixed
 }
 
+/// The domain tag prepended to a public key when producing a proof of possession.
+///
+/// Ensures that a proof of possession can never be mistaken for a signature over some other
+/// message.
+pub const PROOF_OF_POSSESSION_DOMAIN: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// Returns the message that is signed to prove possession of the secret key for `pubkey`.
+pub(crate) fn proof_of_possession_message(pubkey: &PublicKey) -> Vec<u8> {
+    let mut preimage = PROOF_OF_POSSESSION_DOMAIN.to_vec();
+    preimage.append(&mut pubkey.as_bytes());
+
+    hash(&preimage)
+}
+
+/// Returns the message that is signed by `SecretKey::sign_with_domain`: the hash of `msg`
+/// concatenated with the little-endian bytes of `domain`.
+pub fn message_with_domain(msg: &[u8], domain: u64) -> Vec<u8> {
+    let mut preimage = msg.to_vec();
+    preimage.extend_from_slice(&domain.to_le_bytes());
+
+    hash(&preimage)
+}
+
 pub fn bls_verify_aggregate(
     pubkey: &AggregatePublicKey,
     message: &[u8],
@@ -41,6 +41,11 @@ macro_rules! impl_ssz {
     };
 }
 
+/// Implements `TreeHash` for a fixed-length byte type (e.g., `BLSPubkey`), which the spec defines
+/// as a `Vector[byte, $byte_size]`.
+///
+/// Such vectors are composite, so they are never packed. A list of them is hashed as a list of
+/// their individual roots.
 macro_rules! impl_tree_hash {
     ($type: ty, $byte_size: expr) => {
         impl tree_hash::TreeHash for $type {
@@ -1,9 +1,16 @@
-use super::{SecretKey, BLS_PUBLIC_KEY_BYTE_SIZE};
-use milagro_bls::{G1Point, PublicKey as RawPublicKey};
+use super::{
+    bytes_visitor::BytesVisitor, proof_of_possession_message, Error, SecretKey, Signature,
+    BLS_PUBLIC_KEY_BYTE_SIZE, BLS_PUBLIC_KEY_UNCOMPRESSED_BYTE_SIZE,
+};
+use milagro_bls::{
+    AggregatePublicKey as RawAggregatePublicKey, G1Point, PublicKey as RawPublicKey,
+};
 use serde::de::{Deserialize, Deserializer};
 use serde::ser::{Serialize, Serializer};
 use serde_hex::{encode as hex_encode, PrefixedHexVisitor};
 use ssz::{Decode, DecodeError, Encode};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
 use std::default;
 use std::fmt;
 use std::hash::{Hash, Hasher};
@@ -24,6 +31,75 @@ impl PublicKey {
         Self(raw)
     }
 
+    /// Aggregates the given `keys` into a single `PublicKey`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns `Error::EmptyAggregate` if `keys` is empty, rather than returning the point at
+    /// infinity.
+    pub fn aggregate<'a>(keys: impl IntoIterator<Item = &'a PublicKey>) -> Result<Self, Error> {
+        let mut keys = keys.into_iter().peekable();
+
+        if keys.peek().is_none() {
+            return Err(Error::EmptyAggregate);
+        }
+
+        let mut aggregate = RawAggregatePublicKey::new();
+        keys.for_each(|key| aggregate.add(key.as_raw()));
+
+        Ok(PublicKey(RawPublicKey::new_from_raw(&aggregate.point)))
+    }
+
+    /// Aggregates the given `keys` into a single `PublicKey`, as per `aggregate`.
+    ///
+    /// Points are accumulated in projective coordinates and only converted to affine once, at the
+    /// end. This is significantly faster than `aggregate` when aggregating large committees.
+    ///
+    /// ## Errors
+    ///
+    /// Returns `Error::EmptyAggregate` if `keys` is empty.
+    pub fn aggregate_fast(keys: &[PublicKey]) -> Result<Self, Error> {
+        let (first, rest) = keys.split_first().ok_or(Error::EmptyAggregate)?;
+
+        let mut point = first.as_point().clone();
+        rest.iter().for_each(|key| point.add(key.as_point()));
+        point.affine();
+
+        Ok(PublicKey(RawPublicKey::new_from_raw(&point)))
+    }
+
+    /// Decompresses and aggregates the given compressed `keys` in a single pass, without
+    /// collecting them into intermediate `PublicKey`s.
+    ///
+    /// Each key is subject to the same checks as `Self::from_bytes`.
+    ///
+    /// ## Errors
+    ///
+    /// - `Error::EmptyAggregate` if `keys` is empty.
+    /// - `Error::InvalidPublicKey` for the first key which is invalid.
+    pub fn combine_serialized(keys: &[[u8; BLS_PUBLIC_KEY_BYTE_SIZE]]) -> Result<Self, Error> {
+        if keys.is_empty() {
+            return Err(Error::EmptyAggregate);
+        }
+
+        let mut aggregate = RawAggregatePublicKey::new();
+        for (index, bytes) in keys.iter().enumerate() {
+            let pubkey = Self::from_bytes(bytes)
+                .map_err(|error| Error::InvalidPublicKey { index, error })?;
+            aggregate.point.add(pubkey.as_point());
+        }
+        aggregate.point.affine();
+
+        Ok(PublicKey(RawPublicKey::new_from_raw(&aggregate.point)))
+    }
+
+    /// Returns `true` if `signature` proves possession of the secret key for `self`.
+    ///
+    /// See `SecretKey::sign_proof_of_possession`.
+    pub fn verify_proof_of_possession(&self, signature: &Signature) -> bool {
+        signature.verify(&proof_of_possession_message(self), self)
+    }
+
     /// Returns a reference to the underlying signature.
     pub fn as_raw(&self) -> &RawPublicKey {
         &self.0
@@ -44,26 +120,133 @@ impl PublicKey {
         self.as_raw().as_bytes()
     }
 
-    /// Converts compressed bytes to PublicKey
+    /// Converts compressed bytes to PublicKey.
+    ///
+    /// Returns an error if the point is not on the curve, is the point at infinity or is not in
+    /// the G1 subgroup (i.e., `KeyValidate` in the BLS signature specification).
+    ///
+    /// This is the decoding used by SSZ, serde and `TryFrom`.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
-        let pubkey = RawPublicKey::from_bytes(&bytes).map_err(|_| {
-            DecodeError::BytesInvalid(format!("Invalid PublicKey bytes: {:?}", bytes))
+        let pubkey = Self::from_bytes_unchecked(bytes)?;
+
+        pubkey.validate().map_err(|reason| {
+            DecodeError::BytesInvalid(format!("Invalid PublicKey bytes: {}: {:?}", reason, bytes))
+        })?;
+
+        Ok(pubkey)
+    }
+
+    /// Converts compressed bytes to PublicKey without checking that the point is in the G1
+    /// subgroup, nor that it is not the point at infinity.
+    ///
+    /// Subgroup checks are expensive. This function should only be used for keys that have
+    /// already been validated (e.g., keys read from our own database).
+    pub fn from_bytes_unchecked(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let pubkey = RawPublicKey::from_bytes_unchecked(&bytes).map_err(|_| {
+            DecodeError::BytesInvalid(format!(
+                "Invalid PublicKey bytes: point is not on the curve: {:?}",
+                bytes
+            ))
         })?;
 
         Ok(PublicKey(pubkey))
     }
 
+    /// Converts many compressed public keys into `PublicKey`s.
+    ///
+    /// All points are decompressed before any subgroup checks are performed, so malformed input
+    /// is rejected before any expensive work is done.
+    ///
+    /// ## Errors
+    ///
+    /// Returns the index of the first invalid key, alongside the reason it is invalid.
+    pub fn from_bytes_many(
+        bytes: &[[u8; BLS_PUBLIC_KEY_BYTE_SIZE]],
+    ) -> Result<Vec<Self>, (usize, DecodeError)> {
+        let pubkeys = bytes
+            .iter()
+            .enumerate()
+            .map(|(i, bytes)| Self::from_bytes_unchecked(bytes).map_err(|e| (i, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (i, pubkey) in pubkeys.iter().enumerate() {
+            pubkey.validate().map_err(|reason| {
+                (
+                    i,
+                    DecodeError::BytesInvalid(format!(
+                        "Invalid PublicKey bytes: {}: {:?}",
+                        reason,
+                        &bytes[i][..]
+                    )),
+                )
+            })?;
+        }
+
+        Ok(pubkeys)
+    }
+
+    /// Decodes SSZ `bytes` as per `Decode::from_ssz_bytes`, but always returns
+    /// `DecodeError::BytesInvalid` if the key is the point at infinity.
+    ///
+    /// Intended for consensus objects which must never contain the infinity key.
+    pub fn decode_nonzero(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let pubkey = Self::from_ssz_bytes(bytes)?;
+
+        if pubkey.is_infinity() {
+            Err(DecodeError::BytesInvalid(format!(
+                "Invalid PublicKey bytes: point at infinity: {:?}",
+                bytes
+            )))
+        } else {
+            Ok(pubkey)
+        }
+    }
+
+    /// Returns `true` if `self` is the point at infinity (the identity element).
+    pub fn is_infinity(&self) -> bool {
+        self.as_point().is_infinity()
+    }
+
+    /// Returns the reason `self` is not a valid public key, if any.
+    ///
+    /// The point at infinity is the identity element; accepting it as a public key allows
+    /// rogue-key style attacks on aggregate signatures.
+    fn validate(&self) -> Result<(), &'static str> {
+        if self.is_infinity() {
+            Err("point at infinity")
+        } else if !self.as_raw().key_validate() {
+            Err("point is not in the G1 subgroup")
+        } else {
+            Ok(())
+        }
+    }
+
     /// Returns the PublicKey as (x, y) bytes
     pub fn as_uncompressed_bytes(&self) -> Vec<u8> {
         RawPublicKey::as_uncompressed_bytes(&mut self.0.clone())
     }
 
     /// Converts (x, y) bytes to PublicKey
+    ///
+    /// Returns an error if `bytes` is not `BLS_PUBLIC_KEY_UNCOMPRESSED_BYTE_SIZE` long, or if the
+    /// point is not on the curve or is not in the G1 subgroup.
     pub fn from_uncompressed_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
-        let pubkey = RawPublicKey::from_uncompressed_bytes(&bytes).map_err(|_| {
+        if bytes.len() != BLS_PUBLIC_KEY_UNCOMPRESSED_BYTE_SIZE {
+            return Err(DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: BLS_PUBLIC_KEY_UNCOMPRESSED_BYTE_SIZE,
+            });
+        }
+
+        let pubkey = PublicKey(RawPublicKey::from_uncompressed_bytes(&bytes).map_err(|_| {
             DecodeError::BytesInvalid("Invalid PublicKey uncompressed bytes.".to_string())
+        })?);
+
+        pubkey.validate().map_err(|reason| {
+            DecodeError::BytesInvalid(format!("Invalid PublicKey uncompressed bytes: {}", reason))
         })?;
-        Ok(PublicKey(pubkey))
+
+        Ok(pubkey)
     }
 
     /// Returns the last 6 bytes of the SSZ encoding of the public key, as a hex string.
@@ -82,8 +265,9 @@ impl PublicKey {
 }
 
 impl fmt::Display for PublicKey {
+    /// Displays the full `0x`-prefixed hex encoding of the public key, as used by serde.
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.concatenated_hex_id())
+        write!(f, "{}", self.as_hex_string())
     }
 }
 
@@ -105,11 +289,17 @@ impl_ssz!(PublicKey, BLS_PUBLIC_KEY_BYTE_SIZE, "PublicKey");
 impl_tree_hash!(PublicKey, BLS_PUBLIC_KEY_BYTE_SIZE);
 
 impl Serialize for PublicKey {
+    /// Serializes as `0x`-prefixed hex for human-readable formats (e.g., JSON) and as raw bytes
+    /// otherwise (e.g., bincode).
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        serializer.serialize_str(&hex_encode(self.as_raw().as_bytes()))
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&hex_encode(self.as_raw().as_bytes()))
+        } else {
+            serializer.serialize_bytes(&self.as_raw().as_bytes())
+        }
     }
 }
 
@@ -118,19 +308,73 @@ impl<'de> Deserialize<'de> for PublicKey {
     where
         D: Deserializer<'de>,
     {
-        let bytes = deserializer.deserialize_str(PrefixedHexVisitor)?;
+        let bytes = if deserializer.is_human_readable() {
+            deserializer.deserialize_str(PrefixedHexVisitor)?
+        } else {
+            deserializer.deserialize_bytes(BytesVisitor)?
+        };
+        if bytes.len() != BLS_PUBLIC_KEY_BYTE_SIZE {
+            return Err(serde::de::Error::custom(format!(
+                "invalid pubkey length: expected {} bytes, got {}",
+                BLS_PUBLIC_KEY_BYTE_SIZE,
+                bytes.len()
+            )));
+        }
         let pubkey = Self::from_ssz_bytes(&bytes[..])
             .map_err(|e| serde::de::Error::custom(format!("invalid pubkey ({:?})", e)))?;
         Ok(pubkey)
     }
 }
 
+impl TryFrom<[u8; BLS_PUBLIC_KEY_BYTE_SIZE]> for PublicKey {
+    type Error = DecodeError;
+
+    /// Equivalent to `Self::from_bytes`.
+    fn try_from(bytes: [u8; BLS_PUBLIC_KEY_BYTE_SIZE]) -> Result<Self, DecodeError> {
+        Self::from_bytes(&bytes)
+    }
+}
+
+impl TryFrom<&[u8]> for PublicKey {
+    type Error = DecodeError;
+
+    /// Equivalent to `Self::from_bytes`, but first returns `DecodeError::InvalidByteLength` if
+    /// `bytes` is not `BLS_PUBLIC_KEY_BYTE_SIZE` long.
+    fn try_from(bytes: &[u8]) -> Result<Self, DecodeError> {
+        if bytes.len() != BLS_PUBLIC_KEY_BYTE_SIZE {
+            return Err(DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: BLS_PUBLIC_KEY_BYTE_SIZE,
+            });
+        }
+
+        Self::from_bytes(bytes)
+    }
+}
+
 impl PartialEq for PublicKey {
+    /// Compares the compressed bytes, rather than the underlying points.
+    ///
+    /// Serialization always normalizes the point, so two keys for the same point are equal even if
+    /// the backend stores them differently. This keeps `Eq` consistent with `Hash`.
     fn eq(&self, other: &PublicKey) -> bool {
         self.as_ssz_bytes() == other.as_ssz_bytes()
     }
 }
 
+impl PartialOrd for PublicKey {
+    fn partial_cmp(&self, other: &PublicKey) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PublicKey {
+    /// Orders public keys lexicographically by their compressed bytes.
+    fn cmp(&self, other: &PublicKey) -> Ordering {
+        self.as_bytes().cmp(&other.as_bytes())
+    }
+}
+
 impl Hash for PublicKey {
     /// Note: this is distinct from consensus serialization, it will produce a different hash.
     ///
@@ -154,6 +398,7 @@ impl arbitrary::Arbitrary for PublicKey {
 
 #[cfg(test)]
 mod tests {
+    use super::super::{AggregatePublicKey, BLS_SECRET_KEY_BYTE_SIZE};
     use super::*;
     use ssz::ssz_encode;
 
@@ -168,6 +413,26 @@ mod tests {
         assert_eq!(original, decoded);
     }
 
+    #[test]
+    pub fn test_uncompressed_round_trip() {
+        let original = PublicKey::from_secret_key(&SecretKey::random());
+
+        let compressed = PublicKey::from_bytes(&original.as_bytes()).unwrap();
+        let uncompressed = compressed.as_uncompressed_bytes();
+        assert_eq!(uncompressed.len(), BLS_PUBLIC_KEY_UNCOMPRESSED_BYTE_SIZE);
+
+        let decoded = PublicKey::from_uncompressed_bytes(&uncompressed).unwrap();
+        assert_eq!(decoded, original);
+
+        assert_eq!(
+            PublicKey::from_uncompressed_bytes(&original.as_bytes()),
+            Err(DecodeError::InvalidByteLength {
+                len: BLS_PUBLIC_KEY_BYTE_SIZE,
+                expected: BLS_PUBLIC_KEY_UNCOMPRESSED_BYTE_SIZE
+            })
+        );
+    }
+
     #[test]
     pub fn test_byte_size() {
         let sk = SecretKey::random();
@@ -176,4 +441,392 @@ mod tests {
         let bytes = ssz_encode(&original);
         assert_eq!(bytes.len(), BLS_PUBLIC_KEY_BYTE_SIZE);
     }
+
+    fn infinity_bytes() -> Vec<u8> {
+        let mut bytes = vec![0; BLS_PUBLIC_KEY_BYTE_SIZE];
+        bytes[0] = 0xc0;
+        bytes
+    }
+
+    /// A point that is on the curve, but not in the G1 subgroup.
+    ///
+    /// Taken from the `deserialization_fails_not_in_G1` BLS test vector.
+    const NOT_IN_G1: &str = "8123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    #[test]
+    pub fn test_from_bytes_not_in_subgroup() {
+        let bytes = hex::decode(NOT_IN_G1).unwrap();
+
+        assert!(PublicKey::from_bytes_unchecked(&bytes).is_ok());
+
+        match PublicKey::from_bytes(&bytes) {
+            Err(DecodeError::BytesInvalid(msg)) => assert!(msg.contains("subgroup")),
+            _ => panic!("expected subgroup check to fail"),
+        }
+    }
+
+    #[test]
+    pub fn test_from_bytes_many() {
+        let pubkeys: Vec<PublicKey> = (0..4)
+            .map(|_| PublicKey::from_secret_key(&SecretKey::random()))
+            .collect();
+        let mut bytes: Vec<[u8; BLS_PUBLIC_KEY_BYTE_SIZE]> = pubkeys
+            .iter()
+            .map(|pubkey| {
+                let mut array = [0; BLS_PUBLIC_KEY_BYTE_SIZE];
+                array.copy_from_slice(&pubkey.as_bytes());
+                array
+            })
+            .collect();
+
+        let decoded = PublicKey::from_bytes_many(&bytes).unwrap();
+        let individually: Vec<PublicKey> = bytes
+            .iter()
+            .map(|bytes| PublicKey::from_bytes(bytes).unwrap())
+            .collect();
+        assert_eq!(decoded, pubkeys);
+        assert_eq!(decoded, individually);
+
+        bytes[2].copy_from_slice(&hex::decode(NOT_IN_G1).unwrap());
+        match PublicKey::from_bytes_many(&bytes) {
+            Err((i, _)) => assert_eq!(i, 2),
+            Ok(_) => panic!("expected an invalid key"),
+        }
+    }
+
+    #[test]
+    pub fn test_is_infinity() {
+        let infinity = PublicKey::from_raw(RawPublicKey::new_from_raw(&G1Point::new()));
+        assert!(infinity.is_infinity());
+
+        let pubkey = PublicKey::from_secret_key(&SecretKey::random());
+        assert!(!pubkey.is_infinity());
+    }
+
+    #[test]
+    pub fn test_from_bytes_infinity() {
+        let bytes = infinity_bytes();
+
+        assert!(PublicKey::from_bytes_unchecked(&bytes)
+            .unwrap()
+            .is_infinity());
+
+        match PublicKey::from_bytes(&bytes) {
+            Err(DecodeError::BytesInvalid(msg)) => assert!(msg.contains("infinity")),
+            other => panic!("expected infinity to be rejected, got {:?}", other),
+        }
+        assert!(PublicKey::from_ssz_bytes(&bytes).is_err());
+        assert!(PublicKey::try_from(&bytes[..]).is_err());
+        assert!(
+            serde_json::from_str::<PublicKey>(&format!("\"0x{}\"", hex::encode(&bytes))).is_err()
+        );
+
+        let mut many = [[0; BLS_PUBLIC_KEY_BYTE_SIZE]; 2];
+        many[0].copy_from_slice(&PublicKey::from_secret_key(&SecretKey::random()).as_bytes());
+        many[1].copy_from_slice(&bytes);
+        match PublicKey::from_bytes_many(&many) {
+            Err((i, _)) => assert_eq!(i, 1),
+            Ok(_) => panic!("expected infinity to be rejected"),
+        }
+
+        let pubkey = PublicKey::from_secret_key(&SecretKey::random());
+        assert_eq!(PublicKey::from_bytes(&pubkey.as_bytes()).unwrap(), pubkey);
+    }
+
+    #[test]
+    pub fn test_ssz_decode_infinity() {
+        match PublicKey::from_ssz_bytes(&infinity_bytes()) {
+            Err(DecodeError::BytesInvalid(msg)) => assert!(msg.contains("infinity")),
+            other => panic!("expected infinity to be rejected, got {:?}", other),
+        }
+    }
+
+    #[test]
+    pub fn test_decode_nonzero() {
+        match PublicKey::decode_nonzero(&infinity_bytes()) {
+            Err(DecodeError::BytesInvalid(msg)) => assert!(msg.contains("infinity")),
+            other => panic!("expected infinity to be rejected, got {:?}", other),
+        }
+
+        let pubkey = PublicKey::from_secret_key(&SecretKey::random());
+        assert_eq!(
+            PublicKey::decode_nonzero(&pubkey.as_ssz_bytes()),
+            Ok(pubkey.clone())
+        );
+
+        assert_eq!(
+            PublicKey::decode_nonzero(&pubkey.as_ssz_bytes()[1..]),
+            Err(DecodeError::InvalidByteLength {
+                len: BLS_PUBLIC_KEY_BYTE_SIZE - 1,
+                expected: BLS_PUBLIC_KEY_BYTE_SIZE
+            })
+        );
+    }
+
+    #[test]
+    pub fn test_aggregate_one() {
+        let pubkey = PublicKey::from_secret_key(&SecretKey::random());
+
+        assert_eq!(PublicKey::aggregate(vec![&pubkey]), Ok(pubkey.clone()));
+    }
+
+    #[test]
+    pub fn test_aggregate_many() {
+        let pubkeys: Vec<PublicKey> = (0..4)
+            .map(|_| PublicKey::from_secret_key(&SecretKey::random()))
+            .collect();
+
+        let mut expected = AggregatePublicKey::new();
+        pubkeys.iter().for_each(|pubkey| expected.add(pubkey));
+
+        let aggregate = PublicKey::aggregate(&pubkeys).unwrap();
+        assert_eq!(aggregate.as_bytes(), expected.as_bytes());
+    }
+
+    #[test]
+    pub fn test_aggregate_empty() {
+        assert_eq!(PublicKey::aggregate(&[]), Err(Error::EmptyAggregate));
+    }
+
+    #[test]
+    pub fn test_aggregate_fast() {
+        let pubkeys: Vec<PublicKey> = (0..64)
+            .map(|_| PublicKey::from_secret_key(&SecretKey::random()))
+            .collect();
+
+        for n in &[1, 2, 64] {
+            assert_eq!(
+                PublicKey::aggregate_fast(&pubkeys[0..*n]),
+                PublicKey::aggregate(&pubkeys[0..*n])
+            );
+        }
+
+        assert_eq!(PublicKey::aggregate_fast(&[]), Err(Error::EmptyAggregate));
+    }
+
+    #[test]
+    pub fn test_combine_serialized() {
+        let pubkeys: Vec<PublicKey> = (0..8)
+            .map(|_| PublicKey::from_secret_key(&SecretKey::random()))
+            .collect();
+        let mut bytes: Vec<[u8; BLS_PUBLIC_KEY_BYTE_SIZE]> = pubkeys
+            .iter()
+            .map(|pubkey| {
+                let mut bytes = [0; BLS_PUBLIC_KEY_BYTE_SIZE];
+                bytes.copy_from_slice(&pubkey.as_bytes());
+                bytes
+            })
+            .collect();
+
+        let expected = PublicKey::aggregate(&PublicKey::from_bytes_many(&bytes).unwrap());
+        assert_eq!(PublicKey::combine_serialized(&bytes), expected);
+        assert_eq!(
+            PublicKey::combine_serialized(&bytes[0..1]),
+            Ok(pubkeys[0].clone())
+        );
+
+        assert_eq!(
+            PublicKey::combine_serialized(&[]),
+            Err(Error::EmptyAggregate)
+        );
+
+        bytes[3] = [0xff; BLS_PUBLIC_KEY_BYTE_SIZE];
+        bytes[5] = [0xff; BLS_PUBLIC_KEY_BYTE_SIZE];
+        match PublicKey::combine_serialized(&bytes) {
+            Err(Error::InvalidPublicKey { index: 3, .. }) => {}
+            other => panic!("expected invalid key at index 3, got {:?}", other),
+        }
+    }
+
+    #[test]
+    pub fn test_serde_invalid_length() {
+        let hex = hex::encode(PublicKey::from_secret_key(&SecretKey::random()).as_bytes());
+
+        for (json, len) in &[
+            (format!("\"0x{}\"", &hex[2..]), BLS_PUBLIC_KEY_BYTE_SIZE - 1),
+            (format!("\"0x{}00\"", hex), BLS_PUBLIC_KEY_BYTE_SIZE + 1),
+            ("\"0x\"".to_string(), 0),
+        ] {
+            let error = serde_json::from_str::<PublicKey>(json)
+                .expect_err("should reject invalid length")
+                .to_string();
+            assert!(
+                error.contains(&format!("got {}", len)),
+                "unexpected error: {}",
+                error
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_serde_json_round_trip() {
+        let original = PublicKey::from_secret_key(&SecretKey::random());
+
+        let json = serde_json::to_string(&original).unwrap();
+        assert_eq!(json, format!("\"0x{}\"", hex::encode(original.as_bytes())));
+
+        let decoded: PublicKey = serde_json::from_str(&json).unwrap();
+        assert_eq!(original, decoded);
+    }
+
+    #[test]
+    pub fn test_serde_bincode_round_trip() {
+        let original = PublicKey::from_secret_key(&SecretKey::random());
+
+        let encoded = bincode::serialize(&original).unwrap();
+        // A `u64` length prefix followed by the raw bytes.
+        assert_eq!(encoded.len(), 8 + BLS_PUBLIC_KEY_BYTE_SIZE);
+        assert_eq!(&encoded[8..], &original.as_bytes()[..]);
+
+        let decoded: PublicKey = bincode::deserialize(&encoded).unwrap();
+        assert_eq!(original, decoded);
+
+        let short = bincode::serialize(&original.as_bytes()[1..].to_vec()).unwrap();
+        assert!(bincode::deserialize::<PublicKey>(&short).is_err());
+    }
+
+    #[test]
+    pub fn test_proof_of_possession() {
+        let sk = SecretKey::random();
+        let pubkey = PublicKey::from_secret_key(&sk);
+
+        let proof = sk.sign_proof_of_possession();
+        assert!(pubkey.verify_proof_of_possession(&proof));
+
+        let other = PublicKey::from_secret_key(&SecretKey::random());
+        assert!(!other.verify_proof_of_possession(&proof));
+
+        // A regular signature over the public key is not a proof of possession.
+        let signature = Signature::new(&pubkey.as_bytes(), &sk);
+        assert!(!pubkey.verify_proof_of_possession(&signature));
+    }
+
+    #[test]
+    pub fn test_tree_hash_list() {
+        use eth2_hashing::hash;
+        use ssz_types::{typenum::U4, VariableList};
+        use tree_hash::TreeHash;
+
+        let pubkeys: Vec<PublicKey> = (1..=2)
+            .map(|i| {
+                let mut sk = [0; BLS_SECRET_KEY_BYTE_SIZE];
+                sk[BLS_SECRET_KEY_BYTE_SIZE - 1] = i;
+                PublicKey::from_secret_key(&SecretKey::from_bytes(&sk).unwrap())
+            })
+            .collect();
+
+        let concat = |a: &[u8], b: &[u8]| hash(&[a, b].concat());
+
+        // Each key is a `Vector[byte, 48]`, which is two chunks.
+        let roots: Vec<Vec<u8>> = pubkeys
+            .iter()
+            .map(|pubkey| {
+                let mut chunks = [0; 64];
+                chunks[0..BLS_PUBLIC_KEY_BYTE_SIZE].copy_from_slice(&pubkey.as_bytes());
+                hash(&chunks)
+            })
+            .collect();
+        for (pubkey, root) in pubkeys.iter().zip(&roots) {
+            assert_eq!(pubkey.tree_hash_root().as_bytes(), &root[..]);
+        }
+
+        // A `List[BLSPubkey, 4]` merkleizes the key roots to a depth of two, then mixes in the
+        // length.
+        let zero = [0; 32];
+        let mut length = [0; 32];
+        length[0] = 2;
+        let expected = concat(
+            &concat(&concat(&roots[0], &roots[1]), &concat(&zero, &zero)),
+            &length,
+        );
+
+        let list: VariableList<PublicKey, U4> = VariableList::from(pubkeys);
+        assert_eq!(list.tree_hash_root().as_bytes(), &expected[..]);
+    }
+
+    #[test]
+    pub fn test_try_from() {
+        let pubkey = PublicKey::from_secret_key(&SecretKey::random());
+        let mut bytes = [0; BLS_PUBLIC_KEY_BYTE_SIZE];
+        bytes.copy_from_slice(&pubkey.as_bytes());
+
+        assert_eq!(PublicKey::try_from(bytes), Ok(pubkey.clone()));
+        assert_eq!(PublicKey::try_from(&bytes[..]), Ok(pubkey));
+
+        for len in &[
+            0,
+            BLS_PUBLIC_KEY_BYTE_SIZE - 1,
+            BLS_PUBLIC_KEY_BYTE_SIZE + 1,
+        ] {
+            let bytes = vec![0; *len];
+            assert_eq!(
+                PublicKey::try_from(&bytes[..]),
+                Err(DecodeError::InvalidByteLength {
+                    len: *len,
+                    expected: BLS_PUBLIC_KEY_BYTE_SIZE
+                })
+            );
+        }
+
+        let malformed = [0xff; BLS_PUBLIC_KEY_BYTE_SIZE];
+        match PublicKey::try_from(malformed) {
+            Err(DecodeError::BytesInvalid(_)) => {}
+            other => panic!("expected invalid point, got {:?}", other),
+        }
+        assert!(PublicKey::try_from(&malformed[..]).is_err());
+    }
+
+    #[test]
+    pub fn test_eq_hash_consistent() {
+        use std::collections::HashSet;
+
+        let sk = SecretKey::random();
+        let pubkey = PublicKey::from_secret_key(&sk);
+
+        let keys = [
+            PublicKey::from_bytes(&pubkey.as_bytes()).unwrap(),
+            PublicKey::from_uncompressed_bytes(&pubkey.as_uncompressed_bytes()).unwrap(),
+            PublicKey::aggregate(vec![&pubkey]).unwrap(),
+            PublicKey::aggregate_fast(std::slice::from_ref(&pubkey)).unwrap(),
+            pubkey,
+        ];
+
+        let set: HashSet<PublicKey> = keys.iter().cloned().collect();
+        assert_eq!(set.len(), 1);
+        assert!(set.contains(&PublicKey::from_secret_key(&sk)));
+
+        let other = PublicKey::from_secret_key(&SecretKey::random());
+        assert!(!set.contains(&other));
+    }
+
+    #[test]
+    pub fn test_ord() {
+        let mut pubkeys: Vec<PublicKey> = (0..8)
+            .map(|_| PublicKey::from_secret_key(&SecretKey::random()))
+            .collect();
+        let mut reversed = pubkeys.clone();
+        reversed.reverse();
+
+        pubkeys.sort();
+        reversed.sort();
+
+        assert_eq!(pubkeys, reversed, "sorting should be deterministic");
+        for pair in pubkeys.windows(2) {
+            assert!(pair[0].as_bytes() <= pair[1].as_bytes());
+        }
+    }
+
+    #[test]
+    pub fn test_display() {
+        let sk = SecretKey::random();
+        let original = PublicKey::from_secret_key(&sk);
+
+        let display = format!("{}", original);
+        assert_eq!(display.len(), 2 + BLS_PUBLIC_KEY_BYTE_SIZE * 2);
+        assert!(display.starts_with("0x"));
+        assert_eq!(display, display.to_lowercase());
+
+        let decoded = PublicKey::from_bytes(&hex::decode(&display[2..]).unwrap()).unwrap();
+        assert_eq!(original, decoded);
+    }
 }
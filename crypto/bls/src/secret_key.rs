@@ -1,8 +1,47 @@
 extern crate rand;
 
-use crate::PlainText;
+use crate::{
+    message_with_domain, proof_of_possession_message, PlainText, PublicKey, Signature,
+    BLS_SECRET_KEY_BYTE_SIZE,
+};
+use eth2_key_derivation::DerivedKey;
 use milagro_bls::SecretKey as RawSecretKey;
-use ssz::DecodeError;
+use rand::{CryptoRng, RngCore};
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+use ssz::{Decode, DecodeError, Encode};
+use std::fmt;
+use std::mem::{needs_drop, size_of};
+use std::slice;
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::Zeroize;
+
+/// The order of the BLS 12-381 curve, as big-endian bytes.
+///
+/// A valid secret key is a scalar in the range `[1, CURVE_ORDER)`.
+const CURVE_ORDER: [u8; BLS_SECRET_KEY_BYTE_SIZE] = [
+    0x73, 0xed, 0xa7, 0x53, 0x29, 0x9d, 0x7d, 0x48, 0x33, 0x39, 0xd8, 0x08, 0x09, 0xa1, 0xd8, 0x05,
+    0x53, 0xbd, 0xa4, 0x02, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x01,
+];
+
+/// Returns a `Choice` of `1` if the big-endian scalar `bytes` is less than `CURVE_ORDER`.
+///
+/// Computes the borrow of `bytes - CURVE_ORDER` without branching on `bytes`, so it runs in
+/// constant time.
+fn ct_less_than_curve_order(bytes: &[u8]) -> Choice {
+    let borrow = bytes
+        .iter()
+        .zip(CURVE_ORDER.iter())
+        .rev()
+        .fold(0_u16, |borrow, (a, b)| {
+            let diff = u16::from(*a)
+                .wrapping_sub(u16::from(*b))
+                .wrapping_sub(borrow);
+            (diff >> 8) & 1
+        });
+
+    Choice::from(borrow as u8)
+}
 
 /// A single BLS signature.
 ///
@@ -14,13 +53,33 @@ pub struct SecretKey(RawSecretKey);
 impl SecretKey {
     /// Generate a new `Self` using `rand::thread_rng`.
     pub fn random() -> Self {
-        SecretKey(RawSecretKey::random(&mut rand::thread_rng()))
+        Self::random_with_rng(&mut rand::thread_rng())
     }
 
+    /// Generate a new `Self` using the given `rng`.
+    ///
+    /// Useful for generating deterministic keys from a seeded PRNG in tests.
+    pub fn random_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        SecretKey(RawSecretKey::random(rng))
+    }
+
+    /// Wraps `raw` without any validation.
+    ///
+    /// The caller is responsible for ensuring `raw` is non-zero and less than the curve order,
+    /// otherwise it will produce degenerate signatures. Prefer `Self::try_from_raw` unless `raw` is
+    /// from a trusted source and performance is critical.
     pub fn from_raw(raw: RawSecretKey) -> Self {
         Self(raw)
     }
 
+    /// Wraps `raw`, returning an error if it is zero or not less than the curve order.
+    ///
+    /// See `Self::from_bytes` for the validation performed.
+    pub fn try_from_raw(raw: RawSecretKey) -> Result<Self, DecodeError> {
+        let bytes: PlainText = raw.as_bytes().into();
+        Self::from_bytes(bytes.as_bytes())
+    }
+
     /// Returns the secret key as a byte array (wrapped in `PlainText` wrapper so it is zeroized on
     /// `Drop`).
     ///
@@ -32,24 +91,337 @@ impl SecretKey {
 
     /// Instantiate a SecretKey from existing bytes.
     ///
+    /// Returns `DecodeError::InvalidByteLength` if `bytes` is not exactly `BLS_SECRET_KEY_BYTE_SIZE`
+    /// long, before passing them to the backend.
+    ///
+    /// Returns an error if the bytes are all zero or represent a scalar that is not less than the
+    /// curve order, since such keys produce degenerate signatures.
+    ///
+    /// The validity checks are constant-time and errors never include the secret bytes, so that
+    /// the secret cannot leak via timing, logs or error messages.
+    ///
     /// Note: this is _not_ SSZ decoding.
     pub fn from_bytes(bytes: &[u8]) -> Result<SecretKey, DecodeError> {
+        if bytes.len() != BLS_SECRET_KEY_BYTE_SIZE {
+            return Err(DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: BLS_SECRET_KEY_BYTE_SIZE,
+            });
+        }
+
+        let is_zero = bytes.iter().fold(0, |acc, b| acc | b).ct_eq(&0);
+
+        if bool::from(is_zero) {
+            return Err(DecodeError::BytesInvalid(
+                "Invalid SecretKey bytes: secret key is zero".to_string(),
+            ));
+        }
+
+        if !bool::from(ct_less_than_curve_order(bytes)) {
+            return Err(DecodeError::BytesInvalid(
+                "Invalid SecretKey bytes: secret key is not less than the curve order".to_string(),
+            ));
+        }
+
         Ok(SecretKey(RawSecretKey::from_bytes(bytes).map_err(|e| {
             DecodeError::BytesInvalid(format!(
-                "Invalid SecretKey bytes: {:?} Error: {:?}",
-                bytes, e
+                "Invalid SecretKey bytes: length {} Error: {:?}",
+                bytes.len(),
+                e
             ))
         })?))
     }
 
+    /// Instantiate a SecretKey from a hex string, which may or may not have a `0x` prefix.
+    ///
+    /// The decoded bytes must be exactly `BLS_SECRET_KEY_BYTE_SIZE` long.
+    pub fn from_hex(s: &str) -> Result<SecretKey, DecodeError> {
+        let hex = if s.starts_with("0x") { &s[2..] } else { s };
+
+        let bytes = ct_hex_decode(hex)?;
+
+        Self::from_bytes(bytes.as_bytes())
+    }
+
+    /// Derives the EIP-2333 master secret key from some `seed` bytes.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if `seed` is empty.
+    pub fn from_seed(seed: &[u8]) -> Result<SecretKey, DecodeError> {
+        let master = DerivedKey::from_seed(seed)
+            .map_err(|()| DecodeError::BytesInvalid("Seed must not be empty".to_string()))?;
+
+        Self::from_bytes(master.secret())
+    }
+
+    /// Derives the EIP-2333 child of `self` at the given `index`.
+    ///
+    /// ## Errors
+    ///
+    /// EIP-2333 derives child keys modulo the curve order, so this only returns an error in the
+    /// negligible case that the child is zero.
+    pub fn derive_child(&self, index: u32) -> Result<SecretKey, DecodeError> {
+        let parent = DerivedKey::from_secret(self.as_bytes().as_bytes()).map_err(|()| {
+            DecodeError::InvalidByteLength {
+                len: self.as_bytes().len(),
+                expected: BLS_SECRET_KEY_BYTE_SIZE,
+            }
+        })?;
+
+        Self::from_bytes(parent.child(index).secret())
+    }
+
+    /// Returns the public key which corresponds to `self`.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey::from_secret_key(self)
+    }
+
+    /// Signs `msg` with `self`, using the backend's hash-to-curve.
+    pub fn sign(&self, msg: &[u8]) -> Signature {
+        Signature::new(msg, self)
+    }
+
+    /// Signs `msg` under the given `domain`.
+    ///
+    /// The signed message is `message_with_domain(msg, domain)`, so signatures produced under
+    /// different domains are not interchangeable.
+    pub fn sign_with_domain(&self, msg: &[u8], domain: u64) -> Signature {
+        self.sign(&message_with_domain(msg, domain))
+    }
+
+    /// Signs the public key of `self` under a dedicated domain, proving possession of the secret
+    /// key.
+    ///
+    /// Verify with `PublicKey::verify_proof_of_possession`.
+    pub fn sign_proof_of_possession(&self) -> Signature {
+        Signature::new(&proof_of_possession_message(&self.public_key()), self)
+    }
+
     /// Returns the underlying secret key.
     pub(crate) fn as_raw(&self) -> &RawSecretKey {
         &self.0
     }
 }
 
+impl Encode for SecretKey {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        BLS_SECRET_KEY_BYTE_SIZE
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        BLS_SECRET_KEY_BYTE_SIZE
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(self.as_bytes().as_bytes())
+    }
+}
+
+impl Decode for SecretKey {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        BLS_SECRET_KEY_BYTE_SIZE
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let len = bytes.len();
+        let expected = <Self as Decode>::ssz_fixed_len();
+
+        if len != expected {
+            Err(DecodeError::InvalidByteLength { len, expected })
+        } else {
+            SecretKey::from_bytes(bytes)
+        }
+    }
+}
+
+impl PartialEq for SecretKey {
+    /// Compares the serialized keys in constant time, so the comparison does not leak timing
+    /// information about either key.
+    fn eq(&self, other: &SecretKey) -> bool {
+        self.as_bytes()
+            .as_bytes()
+            .ct_eq(other.as_bytes().as_bytes())
+            .into()
+    }
+}
+
+impl Eq for SecretKey {}
+
+// The zeroing in `SecretKey::zeroize` relies on the raw key storing its scalar inline (it is at
+// least `BLS_SECRET_KEY_BYTE_SIZE` bytes) and owning no other memory (it has no drop glue). These
+// fail to compile if a `milagro_bls` upgrade changes either property.
+const _: [(); 0] = [(); (size_of::<RawSecretKey>() < BLS_SECRET_KEY_BYTE_SIZE) as usize];
+const _: [(); 0] = [(); needs_drop::<RawSecretKey>() as usize];
+
+impl SecretKey {
+    /// Overwrites the memory of the underlying raw key with zeros.
+    ///
+    /// Leaves `self` as the zero scalar, which `Self::from_bytes` rejects, so this is private and
+    /// only used when `self` is dropped.
+    fn zeroize(&mut self) {
+        // SAFETY: `milagro_bls` does not expose a way to zero a secret key. The pointer is derived
+        // from a unique borrow of `self.0`, so it is valid, aligned and not aliased for
+        // `size_of::<RawSecretKey>()` bytes for the lifetime of `bytes`. The raw key is composed
+        // entirely of integer limbs (checked by the assertions above), so every byte is
+        // initialized and an all-zero bit pattern is a valid value.
+        let bytes = unsafe {
+            slice::from_raw_parts_mut(
+                &mut self.0 as *mut RawSecretKey as *mut u8,
+                size_of::<RawSecretKey>(),
+            )
+        };
+        bytes.zeroize();
+    }
+}
+
+impl Drop for SecretKey {
+    /// Ensures the secret key is not left lingering in memory once it is dropped.
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl Serialize for SecretKey {
+    /// Serializes as `0x`-prefixed hex, consistent with `PublicKey`.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut hex = ct_hex_encode(self.as_bytes().as_bytes());
+        let result = serializer.serialize_str(&hex);
+        hex.zeroize();
+        result
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let bytes = deserializer.deserialize_str(SecretKeyVisitor)?;
+        Self::from_bytes(bytes.as_bytes())
+            .map_err(|e| serde::de::Error::custom(format!("invalid secret key ({:?})", e)))
+    }
+}
+
+/// Deserializes `0x`-prefixed hex into a `PlainText`, using `ct_hex_decode`.
+///
+/// Used instead of `serde_hex::PrefixedHexVisitor`, which decodes in variable time and returns a
+/// `Vec<u8>` that is not zeroized.
+struct SecretKeyVisitor;
+
+impl<'de> Visitor<'de> for SecretKeyVisitor {
+    type Value = PlainText;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a hex string with 0x prefix")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if value.starts_with("0x") {
+            ct_hex_decode(&value[2..])
+                .map_err(|e| de::Error::custom(format!("invalid hex ({:?})", e)))
+        } else {
+            Err(de::Error::custom("missing 0x prefix"))
+        }
+    }
+}
+
+/// Decodes the (unprefixed) `hex` string into a `PlainText`.
+///
+/// Runs in time which depends only upon the length of `hex`, not its contents. The error never
+/// includes any part of `hex`.
+fn ct_hex_decode(hex: &str) -> Result<PlainText, DecodeError> {
+    let hex = hex.as_bytes();
+
+    if hex.len() & 1 == 1 {
+        return Err(DecodeError::BytesInvalid(
+            "SecretKey hex has an odd length".to_string(),
+        ));
+    }
+
+    let mut bytes = PlainText::zero(hex.len() / 2);
+    let mut valid = 0xff;
+
+    for (byte, pair) in bytes.as_mut_bytes().iter_mut().zip(hex.chunks(2)) {
+        let (high, high_valid) = ct_hex_digit(pair[0]);
+        let (low, low_valid) = ct_hex_digit(pair[1]);
+
+        *byte = (high << 4) | low;
+        valid &= high_valid & low_valid;
+    }
+
+    if bool::from(valid.ct_eq(&0xff)) {
+        Ok(bytes)
+    } else {
+        Err(DecodeError::BytesInvalid(
+            "SecretKey hex is not valid hex".to_string(),
+        ))
+    }
+}
+
+/// Encodes `bytes` as `0x`-prefixed, lower-case hex.
+///
+/// Used instead of `serde_hex::encode`, which leaves an intermediate copy of the hex on the heap.
+/// The returned `String` is allocated at its final size so it is never reallocated; callers
+/// should zeroize it once done.
+fn ct_hex_encode(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(2 + bytes.len() * 2);
+    hex.push_str("0x");
+
+    for byte in bytes {
+        hex.push(char::from(ct_hex_char(byte >> 4)));
+        hex.push(char::from(ct_hex_char(byte & 0x0f)));
+    }
+
+    hex
+}
+
+/// Returns the lower-case hex digit for `nibble`, which must be less than 16.
+///
+/// Uses only arithmetic and bitwise operations, so it doesn't branch on (or index by) `nibble`.
+fn ct_hex_char(nibble: u8) -> u8 {
+    // `0x27` is the distance from `'9' + 1` to `'a'`, only added when `nibble` is greater than 9.
+    let nibble = i16::from(nibble);
+    (nibble + i16::from(b'0') + (((9 - nibble) >> 8) & 0x27)) as u8
+}
+
+/// Returns the value of the hex digit `c` (either case) and a mask which is `0xff` if `c` is a
+/// valid hex digit, or `0` otherwise. The value is `0` if `c` is invalid.
+///
+/// Uses only arithmetic and bitwise operations, so it doesn't branch on (or index by) `c`.
+fn ct_hex_digit(c: u8) -> (u8, u8) {
+    // `num` is in `0..10` iff `c` is in `'0'..='9'`.
+    let num = c ^ b'0';
+    let num_valid = (u16::from(num).wrapping_sub(10) >> 8) as u8;
+
+    // `alpha` is in `10..16` iff `c` is in `'A'..='F'` or `'a'..='f'`.
+    let alpha = (c & !0x20).wrapping_sub(b'A' - 10);
+    let alpha_valid =
+        ((u16::from(alpha).wrapping_sub(10) ^ u16::from(alpha).wrapping_sub(16)) >> 8) as u8;
+
+    (
+        (num & num_valid) | (alpha & alpha_valid),
+        num_valid | alpha_valid,
+    )
+}
+
 #[cfg(test)]
 mod tests {
+    use super::super::{AggregateSignature, BLS_PUBLIC_KEY_BYTE_SIZE};
     use super::*;
 
     #[test]
@@ -65,4 +437,340 @@ mod tests {
 
         assert!(original.as_bytes() == decoded.as_bytes());
     }
+
+    #[test]
+    pub fn test_from_bytes_zero() {
+        match SecretKey::from_bytes(&[0; BLS_SECRET_KEY_BYTE_SIZE]) {
+            Err(DecodeError::BytesInvalid(_)) => {}
+            _ => panic!("expected zero secret key to be invalid"),
+        }
+    }
+
+    #[test]
+    pub fn test_from_bytes_curve_order() {
+        match SecretKey::from_bytes(&CURVE_ORDER) {
+            Err(DecodeError::BytesInvalid(_)) => {}
+            _ => panic!("expected curve order secret key to be invalid"),
+        }
+    }
+
+    #[test]
+    pub fn test_from_bytes_error_excludes_secret() {
+        let mut above_order = CURVE_ORDER;
+        above_order[BLS_SECRET_KEY_BYTE_SIZE - 1] = 0xff;
+
+        for bytes in &[CURVE_ORDER, above_order] {
+            match SecretKey::from_bytes(bytes) {
+                Err(DecodeError::BytesInvalid(msg)) => {
+                    assert!(!msg.contains(&format!("{:?}", &bytes[..])));
+                    assert!(!msg.contains(&hex::encode(bytes)));
+                    assert!(!msg.contains(&format!("{}", bytes[0])));
+                }
+                _ => panic!("expected secret key to be invalid"),
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_ct_less_than_curve_order() {
+        let less_than = |bytes: &[u8]| bool::from(ct_less_than_curve_order(bytes));
+
+        let mut below = CURVE_ORDER;
+        below[BLS_SECRET_KEY_BYTE_SIZE - 1] -= 1;
+        let mut above = CURVE_ORDER;
+        above[0] += 1;
+
+        assert!(less_than(&below));
+        assert!(less_than(&[0; BLS_SECRET_KEY_BYTE_SIZE]));
+        assert!(!less_than(&CURVE_ORDER));
+        assert!(!less_than(&above));
+        assert!(!less_than(&[0xff; BLS_SECRET_KEY_BYTE_SIZE]));
+    }
+
+    #[test]
+    pub fn test_from_bytes_valid() {
+        let mut bytes = [0; BLS_SECRET_KEY_BYTE_SIZE];
+        bytes[BLS_SECRET_KEY_BYTE_SIZE - 1] = 1;
+
+        assert!(SecretKey::from_bytes(&bytes).is_ok());
+    }
+
+    #[test]
+    pub fn test_try_from_raw() {
+        let original = SecretKey::random();
+
+        match SecretKey::try_from_raw(original.as_raw().clone()) {
+            Ok(sk) => assert!(sk == original),
+            Err(e) => panic!("a valid raw key should pass: {:?}", e),
+        }
+    }
+
+    #[test]
+    pub fn test_random_with_rng() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let keys = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..4)
+                .map(|_| SecretKey::random_with_rng(&mut rng))
+                .collect::<Vec<_>>()
+        };
+
+        let a = keys(42);
+        let b = keys(42);
+        assert!(a.iter().zip(b.iter()).all(|(a, b)| a == b));
+        assert!(a[0] != a[1], "keys from the same rng should differ");
+
+        let c = keys(43);
+        assert!(
+            a[0] != c[0],
+            "different seeds should produce different keys"
+        );
+    }
+
+    #[test]
+    pub fn test_from_bytes_invalid_length() {
+        let bytes = [1; BLS_SECRET_KEY_BYTE_SIZE + 1];
+
+        for len in &[
+            0,
+            BLS_SECRET_KEY_BYTE_SIZE - 1,
+            BLS_SECRET_KEY_BYTE_SIZE + 1,
+        ] {
+            match SecretKey::from_bytes(&bytes[..*len]) {
+                Err(DecodeError::InvalidByteLength {
+                    len: actual,
+                    expected,
+                }) => {
+                    assert_eq!(actual, *len);
+                    assert_eq!(expected, BLS_SECRET_KEY_BYTE_SIZE);
+                }
+                _ => panic!("expected invalid length error for {} bytes", len),
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_ssz_encode_round_trip() {
+        let original = SecretKey::random();
+
+        let bytes = original.as_ssz_bytes();
+        assert_eq!(bytes.len(), BLS_SECRET_KEY_BYTE_SIZE);
+
+        let decoded = SecretKey::from_ssz_bytes(&bytes).unwrap();
+        assert!(original == decoded);
+    }
+
+    #[test]
+    pub fn test_ssz_decode_invalid_length() {
+        let bytes = SecretKey::random().as_ssz_bytes();
+
+        match SecretKey::from_ssz_bytes(&bytes[1..]) {
+            Err(DecodeError::InvalidByteLength { len, expected }) => {
+                assert_eq!(len, BLS_SECRET_KEY_BYTE_SIZE - 1);
+                assert_eq!(expected, BLS_SECRET_KEY_BYTE_SIZE);
+            }
+            _ => panic!("expected invalid length error"),
+        }
+    }
+
+    #[test]
+    pub fn test_hex_round_trip() {
+        let original = SecretKey::random();
+        let hex = hex::encode(original.as_bytes());
+
+        let decoded = SecretKey::from_hex(&hex).unwrap();
+        assert!(original == decoded);
+
+        let decoded = SecretKey::from_hex(&format!("0x{}", hex)).unwrap();
+        assert!(original == decoded);
+    }
+
+    #[test]
+    pub fn test_hex_invalid() {
+        let hex = hex::encode(SecretKey::random().as_bytes());
+
+        assert!(SecretKey::from_hex(&hex[1..]).is_err(), "odd length");
+        assert!(SecretKey::from_hex(&hex[2..]).is_err(), "short length");
+        assert!(
+            SecretKey::from_hex(&format!("{}zz", &hex[2..])).is_err(),
+            "non-hex chars"
+        );
+    }
+
+    #[test]
+    pub fn test_serde_round_trip() {
+        let original = SecretKey::random();
+
+        let json = serde_json::to_string(&original).unwrap();
+        assert_eq!(json, format!("\"0x{}\"", hex::encode(original.as_bytes())));
+
+        let decoded: SecretKey = serde_json::from_str(&json).unwrap();
+        assert!(original == decoded);
+
+        // Should be consistent with `PublicKey`, which is also `0x`-prefixed.
+        let pubkey_json = serde_json::to_string(&original.public_key()).unwrap();
+        assert!(pubkey_json.starts_with("\"0x"));
+
+        let unprefixed = format!("\"{}\"", hex::encode(original.as_bytes()));
+        assert!(serde_json::from_str::<SecretKey>(&unprefixed).is_err());
+    }
+
+    #[test]
+    pub fn test_serde_invalid_hex() {
+        let hex = hex::encode(SecretKey::random().as_bytes());
+
+        for json in &[
+            format!("\"0x{}\"", &hex[1..]),
+            format!("\"0x{}zz\"", &hex[2..]),
+            format!("\"0x{}\"", &hex[2..]),
+        ] {
+            match serde_json::from_str::<SecretKey>(json) {
+                Err(e) => assert!(!e.to_string().contains(&hex[2..])),
+                Ok(_) => panic!("should not decode {}", json),
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_serde_visitor_is_zeroizing() {
+        fn assert_zeroizing<T: Zeroize>(_: &T) {}
+
+        let original = SecretKey::random();
+        let json = serde_json::to_string(&original).unwrap();
+
+        let mut deserializer = serde_json::Deserializer::from_str(&json);
+        let bytes = (&mut deserializer)
+            .deserialize_str(SecretKeyVisitor)
+            .unwrap();
+
+        assert_zeroizing(&bytes);
+        assert!(bytes.as_bytes() == original.as_bytes().as_bytes());
+    }
+
+    #[test]
+    pub fn test_ct_hex_digit() {
+        for c in 0..=u8::MAX {
+            let (value, valid) = ct_hex_digit(c);
+
+            match (c as char).to_digit(16) {
+                Some(expected) => {
+                    assert_eq!(valid, 0xff, "{}", c);
+                    assert_eq!(u32::from(value), expected, "{}", c);
+                }
+                None => {
+                    assert_eq!(valid, 0, "{}", c);
+                    assert_eq!(value, 0, "{}", c);
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_ct_hex_decode() {
+        let bytes: Vec<u8> = (0..=u8::MAX).collect();
+
+        for hex in &[hex::encode(&bytes), hex::encode(&bytes).to_uppercase()] {
+            assert_eq!(ct_hex_decode(hex).unwrap().as_bytes(), &bytes[..]);
+        }
+
+        assert_eq!(ct_hex_decode("").unwrap().len(), 0);
+        assert!(ct_hex_decode("0").is_err());
+        assert!(ct_hex_decode("0g").is_err());
+        assert!(ct_hex_decode(" 0").is_err());
+    }
+
+    #[test]
+    pub fn test_ct_hex_encode() {
+        let bytes: Vec<u8> = (0..=u8::MAX).collect();
+        let hex = ct_hex_encode(&bytes);
+
+        assert_eq!(hex, format!("0x{}", hex::encode(&bytes)));
+        assert_eq!(hex.capacity(), hex.len());
+        assert_eq!(ct_hex_encode(&[]), "0x");
+    }
+
+    #[test]
+    pub fn test_as_bytes_is_zeroizing() {
+        fn assert_zeroizing<T: Zeroize>(_: &T) {}
+
+        let sk = SecretKey::random();
+        let mut bytes = sk.as_bytes();
+
+        assert_zeroizing(&bytes);
+        assert!(std::mem::needs_drop::<PlainText>());
+        assert_eq!(bytes.len(), BLS_SECRET_KEY_BYTE_SIZE);
+
+        bytes.zeroize();
+        assert!(bytes.as_bytes().iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    pub fn test_public_key() {
+        let byte_key = [
+            3, 211, 210, 129, 231, 69, 162, 234, 16, 15, 244, 214, 126, 201, 0, 85, 28, 239, 82,
+            121, 208, 190, 223, 6, 169, 202, 86, 236, 197, 218, 3, 69,
+        ];
+        let sk = SecretKey::from_bytes(&byte_key).unwrap();
+
+        let pubkey = sk.public_key();
+        assert_eq!(pubkey.as_ssz_bytes().len(), BLS_PUBLIC_KEY_BYTE_SIZE);
+        assert_eq!(pubkey, PublicKey::from_secret_key(&sk));
+    }
+
+    #[test]
+    pub fn test_sign() {
+        let sk = SecretKey::random();
+        let pk = sk.public_key();
+        let msg = b"cats";
+
+        let sig = sk.sign(msg);
+        assert!(sig.verify(msg, &pk));
+
+        let mut agg = AggregateSignature::new();
+        agg.add(&sig);
+        assert!(agg.fast_aggregate_verify(msg, &[&pk]));
+    }
+
+    #[test]
+    pub fn test_sign_with_domain() {
+        let sk = SecretKey::random();
+        let pk = sk.public_key();
+        let msg = b"cats";
+
+        let sig = sk.sign_with_domain(msg, 42);
+        assert!(sig.verify(&message_with_domain(msg, 42), &pk));
+
+        #[cfg(not(feature = "fake_crypto"))]
+        {
+            assert!(!sig.verify(msg, &pk), "domain should be included");
+            assert!(
+                !sig.verify(&message_with_domain(msg, 43), &pk),
+                "wrong domain"
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_eq() {
+        let byte_key = [
+            3, 211, 210, 129, 231, 69, 162, 234, 16, 15, 244, 214, 126, 201, 0, 85, 28, 239, 82,
+            121, 208, 190, 223, 6, 169, 202, 86, 236, 197, 218, 3, 69,
+        ];
+        let a = SecretKey::from_bytes(&byte_key).unwrap();
+        let b = SecretKey::from_bytes(&byte_key).unwrap();
+        assert!(a == b);
+
+        assert!(SecretKey::random() != SecretKey::random());
+    }
+
+    #[test]
+    pub fn test_zeroize() {
+        let mut sk = SecretKey::random();
+        assert!(sk.as_bytes().as_bytes().iter().any(|b| *b != 0));
+
+        sk.zeroize();
+
+        assert!(sk.as_bytes().as_bytes().iter().all(|b| *b == 0));
+    }
 }
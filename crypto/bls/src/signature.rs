@@ -56,12 +56,32 @@ impl Signature {
         self.signature.as_bytes()
     }
 
-    // Convert bytes to BLS Signature
+    /// Convert bytes to BLS Signature.
+    ///
+    /// Returns an error if the bytes are not a valid G2 point or if the point is not in the G2
+    /// subgroup.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let signature = Self::from_bytes_unchecked(bytes)?;
+
+        if !signature.is_empty && !signature.signature.subgroup_check() {
+            return Err(DecodeError::BytesInvalid(format!(
+                "Invalid Signature bytes: point is not in the G2 subgroup: {:?}",
+                bytes
+            )));
+        }
+
+        Ok(signature)
+    }
+
+    /// Convert bytes to BLS Signature without checking that the point is in the G2 subgroup.
+    pub fn from_bytes_unchecked(bytes: &[u8]) -> Result<Self, DecodeError> {
         for byte in bytes {
             if *byte != 0 {
                 let raw_signature = RawSignature::from_bytes(&bytes).map_err(|_| {
-                    DecodeError::BytesInvalid(format!("Invalid Signature bytes: {:?}", bytes))
+                    DecodeError::BytesInvalid(format!(
+                        "Invalid Signature bytes: not a valid G2 point: {:?}",
+                        bytes
+                    ))
                 })?;
                 return Ok(Signature {
                     signature: raw_signature,
@@ -138,6 +158,36 @@ mod tests {
         assert_eq!(original, decoded);
     }
 
+    #[test]
+    pub fn test_serde_round_trip() {
+        let keypair = Keypair::random();
+
+        let original = Signature::new(&[42, 42], &keypair.sk);
+
+        let json = serde_json::to_string(&original).unwrap();
+        assert_eq!(json, format!("\"{}\"", original.as_hex_string()));
+
+        let decoded: Signature = serde_json::from_str(&json).unwrap();
+        assert_eq!(original, decoded);
+    }
+
+    /// A point that is on the curve, but not in the G2 subgroup.
+    ///
+    /// Taken from the `deserialization_fails_not_in_G2` BLS test vector.
+    const NOT_IN_G2: &str = "8123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    #[test]
+    pub fn test_from_bytes_not_in_subgroup() {
+        let bytes = hex::decode(NOT_IN_G2).unwrap();
+
+        assert!(Signature::from_bytes_unchecked(&bytes).is_ok());
+
+        match Signature::from_ssz_bytes(&bytes) {
+            Err(DecodeError::BytesInvalid(msg)) => assert!(msg.contains("subgroup")),
+            _ => panic!("expected subgroup check to fail"),
+        }
+    }
+
     #[test]
     pub fn test_byte_size() {
         let keypair = Keypair::random();
@@ -1,4 +1,6 @@
-use crate::{AggregateSignature, PublicKey, Signature};
+use crate::{AggregateSignature, Error, PublicKey, Signature};
+#[cfg(all(feature = "rayon", not(feature = "fake_crypto")))]
+use rayon::prelude::*;
 use std::borrow::Cow;
 
 #[cfg(not(feature = "fake_crypto"))]
@@ -59,8 +61,78 @@ type VerifySet<'a> = (
     &'a [u8],
 );
 
+/// The number of sets verified in each batch by `batch_verify` when the `rayon` feature is
+/// enabled.
+#[cfg(all(feature = "rayon", not(feature = "fake_crypto")))]
+const PARALLEL_BATCH_SIZE: usize = 64;
+
 #[cfg(not(feature = "fake_crypto"))]
 pub fn verify_signature_sets<'a>(sets: Vec<SignatureSet>) -> bool {
+    verify_batched(&sets)
+}
+
+#[cfg(feature = "fake_crypto")]
+pub fn verify_signature_sets<'a>(_: Vec<SignatureSet>) -> bool {
+    true
+}
+
+/// Verifies many independent `(signature, message, public_key)` items.
+///
+/// The signatures are verified together using a random linear combination, which is far cheaper
+/// than verifying each signature individually. If the `rayon` feature is enabled, the items are
+/// split into batches which are verified in parallel.
+///
+/// An empty slice of `items` is considered valid.
+///
+/// ## Errors
+///
+/// - `Error::InvalidSignature` with the index of the first invalid item.
+/// - `Error::BatchVerificationFailed` if the batch is invalid but no single item is (e.g., a
+///   failure in the batched verification itself). This is never treated as success.
+#[cfg(not(feature = "fake_crypto"))]
+pub fn batch_verify(items: &[(AggregateSignature, Message, PublicKey)]) -> Result<(), Error> {
+    if items.is_empty() {
+        return Ok(());
+    }
+
+    let sets: Vec<SignatureSet> = items
+        .iter()
+        .map(|(signature, message, public_key)| {
+            SignatureSet::new(signature, vec![Cow::Borrowed(public_key)], message.clone())
+        })
+        .collect();
+
+    #[cfg(feature = "rayon")]
+    let valid = sets.par_chunks(PARALLEL_BATCH_SIZE).all(verify_batched);
+    #[cfg(not(feature = "rayon"))]
+    let valid = verify_batched(&sets);
+
+    if valid {
+        return Ok(());
+    }
+
+    // Fall back to verifying each set individually to find the culprit.
+    #[cfg(feature = "rayon")]
+    let invalid = sets.par_iter().position_first(|set| !set.is_valid());
+    #[cfg(not(feature = "rayon"))]
+    let invalid = sets.iter().position(|set| !set.is_valid());
+
+    match invalid {
+        Some(index) => Err(Error::InvalidSignature { index }),
+        None => Err(Error::BatchVerificationFailed),
+    }
+}
+
+#[cfg(feature = "fake_crypto")]
+pub fn batch_verify(_: &[(AggregateSignature, Message, PublicKey)]) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Verifies all `sets` with a single random linear combination.
+///
+/// Empty `sets` are passed through to the backend unchanged, matching `verify_signature_sets`.
+#[cfg(not(feature = "fake_crypto"))]
+fn verify_batched(sets: &[SignatureSet]) -> bool {
     let rng = &mut rand::thread_rng();
     let verify_set: Vec<VerifySet> = sets
         .iter()
@@ -69,7 +141,36 @@ pub fn verify_signature_sets<'a>(sets: Vec<SignatureSet>) -> bool {
     RawAggregateSignature::verify_multiple_aggregate_signatures(rng, verify_set.into_iter())
 }
 
-#[cfg(feature = "fake_crypto")]
-pub fn verify_signature_sets<'a>(_: Vec<SignatureSet>) -> bool {
-    true
+#[cfg(test)]
+#[cfg(not(feature = "fake_crypto"))]
+mod tests {
+    use super::*;
+    use crate::Keypair;
+
+    fn item(message: Message) -> (AggregateSignature, Message, PublicKey) {
+        let keypair = Keypair::random();
+
+        let mut signature = AggregateSignature::new();
+        signature.add(&Signature::new(&message, &keypair.sk));
+
+        (signature, message, keypair.pk)
+    }
+
+    #[test]
+    pub fn test_batch_verify() {
+        let mut items: Vec<_> = (0..4).map(|i| item(vec![i; 32])).collect();
+
+        assert_eq!(batch_verify(&items), Ok(()));
+
+        items[2].1 = vec![42; 32];
+        assert_eq!(
+            batch_verify(&items),
+            Err(Error::InvalidSignature { index: 2 })
+        );
+    }
+
+    #[test]
+    pub fn test_batch_verify_empty() {
+        assert_eq!(batch_verify(&[]), Ok(()));
+    }
 }
@@ -0,0 +1,21 @@
+/// The outcome of `AggregateSignature::verify_detailed`.
+///
+/// Intended for diagnosing why an aggregate failed to verify, not for use on hot paths.
+#[derive(Debug, PartialEq, Clone)]
+pub enum VerifyReport {
+    /// The aggregate signature is valid for all message/public key pairs.
+    Valid,
+    /// The messages, public keys and signatures were empty or had differing lengths.
+    LengthMismatch,
+    /// The signatures at these indices are not valid for their message/public key pair.
+    InvalidPairs(Vec<usize>),
+    /// Every signature is individually valid, but they do not sum to the aggregate signature.
+    AggregateMismatch,
+}
+
+impl VerifyReport {
+    /// Returns `true` if `self` is `VerifyReport::Valid`.
+    pub fn is_valid(&self) -> bool {
+        *self == VerifyReport::Valid
+    }
+}
@@ -0,0 +1,23 @@
+#![cfg(test)]
+#![cfg(not(feature = "fake_crypto"))]
+
+use bls::SecretKey;
+
+/// Test case 0 from https://eips.ethereum.org/EIPS/eip-2333.
+///
+/// The full set of vectors is tested in `eth2_key_derivation`; this only ensures that `SecretKey`
+/// delegates to it correctly.
+#[test]
+fn eip2333_test_case_0() {
+    let seed = hex::decode("c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04").unwrap();
+    let master_sk =
+        hex::decode("1baa85baae3855480656e3e77b8e42c6f4be751a2d089e2caab293c39befaa41").unwrap();
+    let child_sk =
+        hex::decode("10675005371da254560dc0c5f9cdf87b305501233b3f69a5f413bea78a67c508").unwrap();
+
+    let master = SecretKey::from_seed(&seed).unwrap();
+    assert_eq!(master.as_bytes().as_bytes(), &master_sk[..], "master");
+
+    let child = master.derive_child(0).unwrap();
+    assert_eq!(child.as_bytes().as_bytes(), &child_sk[..], "child");
+}
@@ -52,6 +52,22 @@ impl DerivedKey {
         }
     }
 
+    /// Instantiates `Self` from some existing BLS secret key, allowing children of an arbitrary
+    /// key to be derived.
+    ///
+    /// ## Errors
+    ///
+    /// Returns `Err(())` if `secret.len() != HASH_SIZE`, otherwise always returns `Ok(self)`.
+    pub fn from_secret(secret: &[u8]) -> Result<Self, ()> {
+        if secret.len() != HASH_SIZE {
+            Err(())
+        } else {
+            let mut hash = SecretHash::zero();
+            hash.as_mut_bytes().copy_from_slice(secret);
+            Ok(Self(hash))
+        }
+    }
+
     /// Derives a child key from the secret `Self` at some `index`.
     pub fn child(&self, index: u32) -> DerivedKey {
         Self(derive_child_sk(self.0.as_bytes(), index))
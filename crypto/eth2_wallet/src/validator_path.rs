@@ -1,5 +1,6 @@
 use std::fmt;
 use std::iter::Iterator;
+use std::str::FromStr;
 
 pub const PURPOSE: u32 = 12381;
 pub const COIN_TYPE: u32 = 3600;
@@ -39,3 +40,30 @@ impl fmt::Display for ValidatorPath {
         Ok(())
     }
 }
+
+impl FromStr for ValidatorPath {
+    type Err = String;
+
+    /// Parses an EIP-2334 path (e.g., `m/12381/3600/0/0/0`), which must start with the master node
+    /// `m` followed by zero or more `/`-separated decimal indices.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid derivation path: {:?}", s);
+
+        let mut nodes = s.split('/');
+
+        if nodes.next() != Some("m") {
+            return Err(invalid());
+        }
+
+        nodes
+            .map(|node| {
+                if node.is_empty() || !node.bytes().all(|b| b.is_ascii_digit()) {
+                    Err(invalid())
+                } else {
+                    node.parse::<u32>().map_err(|_| invalid())
+                }
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
@@ -5,6 +5,7 @@ use crate::{
     },
     KeyType, ValidatorPath,
 };
+use bip39::Language;
 use eth2_keystore::{
     decrypt, default_kdf, encrypt, keypair_from_secret, Keystore, KeystoreBuilder, IV_SIZE,
     SALT_SIZE,
@@ -24,6 +25,8 @@ pub enum Error {
     PathExhausted,
     EmptyPassword,
     EmptySeed,
+    InvalidMnemonic,
+    InvalidPath(String),
 }
 
 impl From<KeystoreError> for Error {
@@ -235,6 +238,26 @@ impl Wallet {
         decrypt(password, &self.json.crypto).map_err(Into::into)
     }
 
+    /// Returns a copy of `self` with the seed re-encrypted using `new_password`.
+    ///
+    /// A new salt and IV are generated using the default encryption settings of `WalletBuilder`,
+    /// whilst the `uuid`, `name` and `nextaccount` are retained.
+    ///
+    /// ## Errors
+    ///
+    /// - If `old_password` is unable to decrypt `self`.
+    /// - If `new_password.is_empty()`.
+    pub fn reencrypt(&self, old_password: &[u8], new_password: &[u8]) -> Result<Self, Error> {
+        let seed = self.decrypt_seed(old_password)?;
+
+        let mut builder =
+            WalletBuilder::from_seed_bytes(seed.as_bytes(), new_password, self.json.name.clone())?;
+        builder.uuid = self.json.uuid;
+        builder.nextaccount = self.json.nextaccount;
+
+        builder.build()
+    }
+
     /// Encodes `self` as a JSON object.
     pub fn to_json_string(&self) -> Result<String, Error> {
         serde_json::to_string(self)
@@ -285,3 +308,29 @@ pub fn recover_validator_secret(
 
     Ok((destination.secret().to_vec().into(), path))
 }
+
+/// Returns the secret for the EIP-2334 `path` (e.g., `m/12381/3600/0/0/0`), derived from the seed
+/// of the BIP-39 mnemonic `phrase` and its `passphrase`.
+///
+/// Like `recover_validator_secret`, this should only be used for recovering lost keys. The secret
+/// can be converted into a key with `SecretKey::from_bytes` (or `keypair_from_secret`).
+///
+/// ## Errors
+///
+/// Returns `Error::InvalidPath` if `path` is malformed and `Error::InvalidMnemonic` if `phrase` is
+/// not a valid English mnemonic. The error never includes the `phrase`.
+pub fn recover_validator_secret_from_mnemonic(
+    phrase: &str,
+    passphrase: &str,
+    path: &str,
+) -> Result<PlainText, Error> {
+    let path: ValidatorPath = path.parse().map_err(Error::InvalidPath)?;
+    let mnemonic =
+        Mnemonic::from_phrase(phrase, Language::English).map_err(|_| Error::InvalidMnemonic)?;
+    let seed = Bip39Seed::new(&mnemonic, passphrase);
+    let master = DerivedKey::from_seed(seed.as_bytes()).map_err(|()| Error::EmptySeed)?;
+
+    let destination = path.iter_nodes().fold(master, |dk, i| dk.child(*i));
+
+    Ok(destination.secret().to_vec().into())
+}
@@ -2,7 +2,8 @@
 
 use eth2_wallet::{
     bip39::{Language, Mnemonic, Seed},
-    recover_validator_secret, DerivedKey, Error, KeyType, KeystoreError, Wallet, WalletBuilder,
+    recover_validator_secret, recover_validator_secret_from_mnemonic, DerivedKey, Error, KeyType,
+    KeystoreError, ValidatorPath, Wallet, WalletBuilder,
 };
 use std::fs::OpenOptions;
 use tempfile::tempdir;
@@ -160,6 +161,54 @@ fn file_round_trip() {
     );
 }
 
+#[test]
+fn reencrypt() {
+    let new_password: &[u8] = &[46; 46];
+    let mut wallet = wallet_from_seed();
+    wallet
+        .next_validator(
+            WALLET_PASSWORD,
+            VOTING_KEYSTORE_PASSWORD,
+            WITHDRAWAL_KEYSTORE_PASSWORD,
+        )
+        .unwrap();
+
+    let reencrypted = wallet.reencrypt(WALLET_PASSWORD, new_password).unwrap();
+
+    assert_eq!(reencrypted.uuid(), wallet.uuid(), "uuid");
+    assert_eq!(reencrypted.name(), wallet.name(), "name");
+    assert_eq!(reencrypted.nextaccount(), 1, "nextaccount");
+
+    assert_eq!(
+        reencrypted.decrypt_seed(WALLET_PASSWORD).err().unwrap(),
+        Error::KeystoreError(KeystoreError::InvalidPassword),
+        "should not decrypt with old password"
+    );
+
+    assert_eq!(
+        reencrypted.decrypt_seed(new_password).unwrap().as_bytes(),
+        SEED,
+        "should decrypt with new password"
+    );
+}
+
+#[test]
+fn reencrypt_bad_password() {
+    let wallet = wallet_from_seed();
+
+    assert_eq!(
+        wallet.reencrypt(&[1, 2, 3], &[46; 46]).err().unwrap(),
+        Error::KeystoreError(KeystoreError::InvalidPassword),
+        "should not reencrypt with bad password"
+    );
+
+    assert_eq!(
+        wallet.reencrypt(WALLET_PASSWORD, &[]).err().unwrap(),
+        Error::EmptyPassword,
+        "should not reencrypt with empty password"
+    );
+}
+
 #[test]
 fn empty_wallet_password() {
     assert_eq!(
@@ -279,3 +328,101 @@ fn key_derivation_from_seed() {
         assert_eq!(wallet.nextaccount(), i + 1, "updated nextaccount");
     }
 }
+
+/// Test case 0 from EIP-2333, where the seed is that of the standard BIP-39 test mnemonic with the
+/// passphrase `TREZOR`.
+const EIP2333_MNEMONIC: &str =
+    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+const EIP2333_PASSPHRASE: &str = "TREZOR";
+const EIP2333_MASTER_SK: &str = "1baa85baae3855480656e3e77b8e42c6f4be751a2d089e2caab293c39befaa41";
+const EIP2333_CHILD_SK: &str = "10675005371da254560dc0c5f9cdf87b305501233b3f69a5f413bea78a67c508";
+
+#[test]
+fn recover_from_mnemonic() {
+    let recover = |path| {
+        recover_validator_secret_from_mnemonic(EIP2333_MNEMONIC, EIP2333_PASSPHRASE, path)
+            .expect("should recover secret")
+            .as_bytes()
+            .to_vec()
+    };
+
+    assert_eq!(recover("m"), hex::decode(EIP2333_MASTER_SK).unwrap());
+    assert_eq!(recover("m/0"), hex::decode(EIP2333_CHILD_SK).unwrap());
+}
+
+#[test]
+fn recover_from_mnemonic_matches_wallet() {
+    let m = Mnemonic::from_phrase(MNEMONIC, Language::English).unwrap();
+    let wallet = WalletBuilder::from_mnemonic(&m, WALLET_PASSWORD, NAME.into())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    for i in 0..4 {
+        let path = ValidatorPath::new(i, KeyType::Voting).to_string();
+        let secret = recover_validator_secret_from_mnemonic(MNEMONIC, "", &path).unwrap();
+
+        assert_eq!(
+            secret.as_bytes(),
+            &recovered_voting_key(&wallet, i)[..],
+            "secret should match wallet for {}",
+            path
+        );
+    }
+}
+
+#[test]
+fn recover_from_invalid_mnemonic() {
+    let invalid_phrases = [
+        "",
+        "abandon",
+        // Invalid checksum.
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon",
+        // Not in the word list.
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon lighthouse",
+    ];
+
+    for phrase in &invalid_phrases {
+        match recover_validator_secret_from_mnemonic(phrase, "", "m/12381/3600/0/0/0") {
+            Err(Error::InvalidMnemonic) => {}
+            _ => panic!("should reject {:?}", phrase),
+        }
+    }
+}
+
+#[test]
+fn recover_from_invalid_path() {
+    let invalid_paths = [
+        "",
+        "m/",
+        "n/12381",
+        "12381/3600",
+        "m//3600",
+        "m/12381/3600/0/0/0/",
+        "m/12381'/3600",
+        "m/-1",
+        "m/+1",
+        "m/4294967296",
+    ];
+
+    for path in &invalid_paths {
+        match recover_validator_secret_from_mnemonic(EIP2333_MNEMONIC, "", path) {
+            Err(Error::InvalidPath(_)) => {}
+            _ => panic!("should reject {:?}", path),
+        }
+    }
+}
+
+#[test]
+fn validator_path_round_trip() {
+    let paths = [
+        ValidatorPath::new(42, KeyType::Voting),
+        ValidatorPath::new(42, KeyType::Withdrawal),
+    ];
+
+    for path in &paths {
+        let parsed: ValidatorPath = path.to_string().parse().unwrap();
+
+        assert_eq!(parsed.to_string(), path.to_string());
+    }
+}